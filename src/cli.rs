@@ -1,14 +1,43 @@
 use getargs::{Arg, Options};
-use std::env::args;
+use std::{env::args, fmt::Display, path::PathBuf, time::Duration};
 
-pub enum CliOptions {
+use crate::{
+    locale::{tr, trf, Language, Message},
+    roll::RollMode,
+};
+
+pub enum CliMode {
     Exit,
+    Help,
     Text,
-    TUI,
-    GUI,
+    Tui,
+    Gui,
     ThreeDimensional,
 }
 
+pub struct CliOptions {
+    pub mode: CliMode,
+    /// Die requested with `--die`, resolved against the state by the caller.
+    pub die: Option<String>,
+    /// Language requested with `--lang`, otherwise detected from the environment.
+    pub language: Option<Language>,
+    pub roll_mode: RollMode,
+    /// Interval between tui updates requested with `--tick-rate`.
+    pub tick_rate: Option<Duration>,
+    /// Config file requested with `--config`, otherwise the default location is tried.
+    pub config: Option<PathBuf>,
+    /// Malformed argument, printed by the caller once the language is set.
+    pub error: Option<CliError>,
+}
+
+/// A malformed command line argument.
+pub enum CliError {
+    MissingDieName,
+    InvalidTickRate(String),
+    MissingConfigPath,
+    UnknownLanguage(String),
+}
+
 pub fn get_cli_options() -> CliOptions {
     let mut args = args().skip(1).collect::<Vec<_>>();
 
@@ -17,41 +46,132 @@ pub fn get_cli_options() -> CliOptions {
     }
 
     let mut opts = Options::new(args.iter().map(String::as_str));
+    let mut mode = None;
+    let mut die = None;
+    let mut language = None;
+    let mut help = false;
+    let mut roll_mode = RollMode::Normal;
+    let mut tick_rate = None;
+    let mut config = None;
 
     while let Some(arg) = opts.next_arg().expect("argument parsing error") {
         match arg {
             Arg::Short('h') | Arg::Long("help") => {
-                eprintln!(
-                    r"Usage: wuerfel.exe [OPTIONS/ARGS]...
-  -h, --help       display this help and exit
-  -c, --text       use raw text output
-  -t, --tui        use tui output
-  -g, --gui        use gui output
-  -3, --3d         use 3d output"
-                );
-
-                return CliOptions::Exit;
+                help = true;
             }
 
             Arg::Short('c') | Arg::Long("text") => {
-                return CliOptions::Text;
+                mode = Some(CliMode::Text);
             }
 
             Arg::Short('t') | Arg::Long("tui") => {
-                return CliOptions::TUI;
+                mode = Some(CliMode::Tui);
             }
 
             Arg::Short('g') | Arg::Long("gui") => {
-                return CliOptions::GUI;
+                mode = Some(CliMode::Gui);
             }
 
             Arg::Short('3') | Arg::Long("3d") => {
-                return CliOptions::ThreeDimensional;
+                mode = Some(CliMode::ThreeDimensional);
+            }
+
+            Arg::Short('d') | Arg::Long("die") => match opts.value() {
+                Ok(value) => die = Some(value.to_string()),
+                Err(_) => return CliOptions::failed(language, CliError::MissingDieName),
+            },
+
+            Arg::Short('a') | Arg::Long("advantage") => {
+                roll_mode = RollMode::Advantage;
+            }
+
+            Arg::Short('D') | Arg::Long("disadvantage") => {
+                roll_mode = RollMode::Disadvantage;
+            }
+
+            Arg::Short('r') | Arg::Long("tick-rate") => {
+                let value = opts.value().unwrap_or_default();
+                match value.parse::<u64>() {
+                    Ok(millis) if millis > 0 => tick_rate = Some(Duration::from_millis(millis)),
+                    _ => {
+                        let error = CliError::InvalidTickRate(value.to_string());
+                        return CliOptions::failed(language, error);
+                    }
+                }
+            }
+
+            Arg::Short('C') | Arg::Long("config") => match opts.value() {
+                Ok(value) => config = Some(PathBuf::from(value)),
+                Err(_) => return CliOptions::failed(language, CliError::MissingConfigPath),
+            },
+
+            Arg::Short('l') | Arg::Long("lang") => {
+                let value = opts.value().unwrap_or_default();
+                match Language::parse(value) {
+                    Some(parsed) => language = Some(parsed),
+                    None => {
+                        let error = CliError::UnknownLanguage(value.to_string());
+                        return CliOptions::failed(language, error);
+                    }
+                }
             }
             _ => {
-                return CliOptions::Exit;
+                return CliOptions::exit(language);
+            }
+        }
+    }
+    if help {
+        mode = Some(CliMode::Help);
+    }
+    CliOptions {
+        mode: mode.unwrap_or(CliMode::Exit),
+        die,
+        language,
+        roll_mode,
+        tick_rate,
+        config,
+        error: None,
+    }
+}
+
+pub fn print_help() {
+    eprintln!("{}", tr(Message::Help));
+}
+
+impl CliOptions {
+    /// Exits without doing anything, in the language parsed so far.
+    fn exit(language: Option<Language>) -> Self {
+        Self {
+            mode: CliMode::Exit,
+            die: None,
+            language,
+            roll_mode: RollMode::Normal,
+            tick_rate: None,
+            config: None,
+            error: None,
+        }
+    }
+
+    /// Exits with `error`, in the language parsed so far.
+    fn failed(language: Option<Language>, error: CliError) -> Self {
+        Self {
+            error: Some(error),
+            ..Self::exit(language)
+        }
+    }
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::MissingDieName => write!(f, "{}", tr(Message::MissingDieName)),
+            CliError::InvalidTickRate(value) => {
+                write!(f, "{}", trf(Message::InvalidTickRate, &[value]))
+            }
+            CliError::MissingConfigPath => write!(f, "{}", tr(Message::MissingConfigPath)),
+            CliError::UnknownLanguage(value) => {
+                write!(f, "{}", trf(Message::UnknownLanguage, &[value]))
             }
         }
     }
-    return CliOptions::Exit;
 }
@@ -0,0 +1,53 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::locale::{trf, Message};
+
+/// Reads the TOML config file given with `--config`, or the one in the user config
+/// directory if it exists.
+///
+/// Without a config file an empty table is returned, so every setting keeps its default.
+pub(crate) fn load(path: Option<&Path>) -> Result<toml::Table, String> {
+    let (path, required) = match path {
+        Some(path) => (path.to_path_buf(), true),
+        None => match default_path() {
+            Some(path) => (path, false),
+            None => return Ok(toml::Table::new()),
+        },
+    };
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound && !required => {
+            return Ok(toml::Table::new())
+        }
+        Err(error) => return Err(trf(Message::ConfigError, &[&path.display(), &error])),
+    };
+    content
+        .parse::<toml::Table>()
+        .map_err(|error| trf(Message::ConfigError, &[&path.display(), &error]))
+}
+
+/// `$XDG_CONFIG_HOME/wuerfel/config.toml`, falling back to `~/.config`.
+fn default_path() -> Option<PathBuf> {
+    let directory = env::var_os("XDG_CONFIG_HOME")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(directory.join("wuerfel").join("config.toml"))
+}
+
+/// The table at a dotted `path` like `tui.keys`, if the config has one.
+pub(crate) fn section<'t>(config: &'t toml::Table, path: &str) -> Option<&'t toml::Table> {
+    path.split('.')
+        .try_fold(config, |table, key| table.get(key)?.as_table())
+}
+
+/// A value as written by the user, without the quotes TOML puts around strings.
+pub(crate) fn describe(value: &toml::Value) -> String {
+    match value.as_str() {
+        Some(text) => text.to_string(),
+        None => value.to_string(),
+    }
+}
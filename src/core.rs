@@ -1,16 +1,52 @@
-use std::{fmt::Display, io};
+use std::{borrow::Cow, fmt::Display, io};
 
-pub(crate) struct State<'a> {
-    dice: Vec<Die<'a>>,
+use oorandom::Rand32;
+use serde::{Deserialize, Serialize};
+
+use crate::roll::{RollMode, RollOptions, RollResult, DEFAULT_TARGET};
+
+use crate::locale::{self, tr, trf, Message};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct State {
+    dice: Vec<Die>,
+    hand: Hand,
+}
+
+/// Most dice a hand may hold, so a typo like `4000000000d6` cannot exhaust memory.
+pub(crate) const MAX_HAND_DICE: u32 = 100;
+
+/// A pool of dice that are rolled together, stored as die index and count.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Hand {
+    entries: Vec<(usize, u32)>,
+}
+
+/// The faces rolled for every die of a hand.
+pub(crate) struct HandRoll {
+    rolls: Vec<(usize, u32)>,
+}
+
+/// File format of an exported dice set.
+#[derive(Serialize, Deserialize)]
+struct DiceFile {
+    dice: Vec<Die>,
+}
+
+/// Result of resolving user input to one of the configured dice.
+pub(crate) enum DieLookup {
+    Found(usize),
+    Ambiguous(Vec<usize>),
+    NotFound { suggestion: Option<usize> },
 }
 
-impl<'a> State<'a> {
-    pub(crate) fn add_die(&mut self, dice: Die<'a>) {
+impl State {
+    pub(crate) fn add_die(&mut self, dice: Die) {
         self.dice.push(dice);
     }
 
     pub(crate) fn print_dice(&self) -> Option<String> {
-        if self.dice.len() == 0 {
+        if self.dice.is_empty() {
             return None;
         }
         let mut buffer = String::new();
@@ -24,23 +60,439 @@ impl<'a> State<'a> {
         Some(buffer)
     }
 
-    pub(crate) fn get_dice(&'a self) -> &'a [Die<'a>] {
+    pub(crate) fn get_dice(&self) -> &[Die] {
         &self.dice
     }
+
+    /// Resolves `query` to a die index.
+    ///
+    /// Names and aliases are compared case-insensitively. Exact matches win over
+    /// prefix matches, and if nothing matches the closest name is suggested.
+    pub(crate) fn find_die(&self, query: &str) -> DieLookup {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return DieLookup::NotFound { suggestion: None };
+        }
+
+        let exact = self.matching_dice(|key| key == query);
+        if !exact.is_empty() {
+            return DieLookup::from_candidates(exact);
+        }
+        let prefixed = self.matching_dice(|key| key.starts_with(&query));
+        if !prefixed.is_empty() {
+            return DieLookup::from_candidates(prefixed);
+        }
+
+        let suggestion = self
+            .dice
+            .iter()
+            .enumerate()
+            .flat_map(|(index, die)| die.lookup_keys().into_iter().map(move |key| (index, key)))
+            .filter_map(|(index, key)| {
+                let distance = edit_distance(&query, &key);
+                let tolerance = query.chars().count().max(key.chars().count()) / 2;
+                (distance <= tolerance).then_some((distance, index))
+            })
+            .min()
+            .map(|(_, index)| index);
+        DieLookup::NotFound { suggestion }
+    }
+
+    /// Human readable description of a lookup result, shared by all frontends.
+    pub(crate) fn describe_lookup(&self, lookup: &DieLookup) -> String {
+        match lookup {
+            DieLookup::Found(index) => trf(Message::LookupFound, &[&self.dice[*index].name]),
+            DieLookup::Ambiguous(candidates) => {
+                let names = candidates
+                    .iter()
+                    .map(|index| self.dice[*index].name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                trf(Message::LookupAmbiguous, &[&names])
+            }
+            DieLookup::NotFound {
+                suggestion: Some(index),
+            } => trf(Message::LookupSuggestion, &[&self.dice[*index].name]),
+            DieLookup::NotFound { suggestion: None } => tr(Message::LookupNotFound).to_string(),
+        }
+    }
+
+    /// Trims `name` and checks that it is not empty and not used by a die other than `except`.
+    pub(crate) fn validate_die_name(
+        &self,
+        name: &str,
+        except: Option<usize>,
+    ) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(tr(Message::EmptyDieName).to_string());
+        }
+        let lowered = name.to_lowercase();
+        let taken = self.dice.iter().enumerate().any(|(index, die)| {
+            Some(index) != except
+                && (die.name.to_lowercase() == lowered
+                    || die
+                        .aliases
+                        .iter()
+                        .any(|alias| alias.to_lowercase() == lowered))
+        });
+        if taken {
+            return Err(trf(Message::DuplicateDieName, &[&name]));
+        }
+        Ok(name.to_string())
+    }
+
+    /// Replaces name and faces of the die at `index`, or adds a new die without one.
+    ///
+    /// Returns the index of the saved die.
+    pub(crate) fn save_die(
+        &mut self,
+        index: Option<usize>,
+        name: String,
+        faces: Vec<Symbol>,
+    ) -> usize {
+        match index.filter(|index| *index < self.dice.len()) {
+            Some(index) => {
+                let die = &mut self.dice[index];
+                die.name = name;
+                die.values = faces;
+                index
+            }
+            None => {
+                self.dice.push(Die {
+                    name,
+                    aliases: vec![],
+                    values: faces,
+                    color: None,
+                });
+                self.dice.len() - 1
+            }
+        }
+    }
+
+    /// Adds a copy of the die at `index` under a free name like `D6 (2)`.
+    pub(crate) fn duplicate_die(&mut self, index: usize) -> Option<usize> {
+        let original = self.dice.get(index)?;
+        let name = (2..)
+            .map(|number| format!("{} ({})", original.name, number))
+            .find(|name| self.validate_die_name(name, None).is_ok())?;
+        let faces = original.values.clone();
+        Some(self.save_die(None, name, faces))
+    }
+
+    /// Removes the die at `index`, dropping it from the hand as well.
+    pub(crate) fn remove_die(&mut self, index: usize) -> Option<Die> {
+        if index >= self.dice.len() {
+            return None;
+        }
+        self.hand.forget(index);
+        Some(self.dice.remove(index))
+    }
+
+    /// Moves the die at `from` to `to`, shifting the dice in between and their hand entries.
+    pub(crate) fn move_die(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.dice.len() || to >= self.dice.len() {
+            return false;
+        }
+        let die = self.dice.remove(from);
+        self.dice.insert(to, die);
+        for (entry, _) in self.hand.entries.iter_mut() {
+            *entry = moved_index(*entry, from, to);
+        }
+        true
+    }
+
+    pub(crate) fn set_die_color(&mut self, index: usize, color: Option<[u8; 3]>) {
+        if let Some(die) = self.dice.get_mut(index) {
+            die.color = color;
+        }
+    }
+
+    /// The dice as TOML, to be read again with [`State::import_dice`].
+    pub(crate) fn export_dice(&self) -> Result<String, String> {
+        let file = DiceFile {
+            dice: self.dice.clone(),
+        };
+        toml::to_string(&file).map_err(|error| error.to_string())
+    }
+
+    /// Replaces the dice with those of an exported dice set and empties the hand.
+    ///
+    /// Names and faces are checked like in the editors, so nothing changes if one of
+    /// the dice is invalid. Returns the number of imported dice.
+    pub(crate) fn import_dice(&mut self, text: &str) -> Result<usize, String> {
+        let file: DiceFile = toml::from_str(text).map_err(|error| error.to_string())?;
+        let imported = State::from_dice(file.dice)?;
+        let count = imported.dice.len();
+        *self = imported;
+        Ok(count)
+    }
+
+    /// A state with `dice` and an empty hand, if every die has a free name and
+    /// distinct consecutive faces.
+    fn from_dice(dice: Vec<Die>) -> Result<State, String> {
+        let mut state = State::default();
+        for die in dice {
+            state
+                .validate_die_name(&die.name, None)
+                .and_then(|_| die.validate_faces())
+                .map_err(|error| trf(Message::InvalidDie, &[&die.name, &error]))?;
+            state.add_die(die);
+        }
+        Ok(state)
+    }
+
+    pub(crate) fn get_hand(&self) -> &Hand {
+        &self.hand
+    }
+
+    pub(crate) fn set_hand(&mut self, hand: Hand) {
+        self.hand = hand;
+    }
+
+    /// Adds one more of the die at `index` to the hand.
+    pub(crate) fn add_to_hand(&mut self, index: usize) {
+        if index < self.dice.len() && self.hand.count() < MAX_HAND_DICE {
+            self.hand.add(index, 1);
+        }
+    }
+
+    /// Removes one of the die at `index` from the hand.
+    pub(crate) fn remove_from_hand(&mut self, index: usize) {
+        self.hand.remove(index);
+    }
+
+    pub(crate) fn clear_hand(&mut self) {
+        self.hand.entries.clear();
+    }
+
+    /// Parses a roll like `2xD6 + D10`, `d20, 3 d4` or `d20 + 5 vs DC 15`.
+    ///
+    /// Each dice term is an optional count followed by a die name, resolved with
+    /// [`State::find_die`]. Plain numbers become the modifier.
+    pub(crate) fn parse_roll(&self, input: &str) -> Result<(Hand, RollOptions), String> {
+        let (options, terms) = RollOptions::parse(input)?;
+        let mut hand = Hand::default();
+        for term in terms.iter() {
+            let digits = term.len() - term.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            let (count, name) = term.split_at(digits);
+            let (count, name) = if count.is_empty() {
+                (1, name)
+            } else {
+                let count = count
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|count| *count > 0)
+                    .ok_or_else(|| trf(Message::InvalidCount, &[&term]))?;
+                (
+                    count,
+                    name.trim_start().trim_start_matches(['x', 'X', '*', '×']),
+                )
+            };
+            if name.trim().is_empty() {
+                return Err(trf(Message::MissingDieInTerm, &[&term]));
+            }
+            match self.find_die(name) {
+                DieLookup::Found(index) => hand.add(index, count),
+                lookup => return Err(self.describe_lookup(&lookup)),
+            }
+            if hand.count() > MAX_HAND_DICE {
+                return Err(trf(Message::TooManyDice, &[&MAX_HAND_DICE]));
+            }
+        }
+        if hand.is_empty() {
+            return Err(tr(Message::EmptyHand).to_string());
+        }
+        if options.mode != RollMode::Normal && hand.single_die().is_none() {
+            return Err(tr(Message::ModeNeedsSingleDie).to_string());
+        }
+        Ok((hand, options))
+    }
+
+    /// Describes a hand as e.g. `2×D6 + D10`.
+    pub(crate) fn describe_hand(&self, hand: &Hand) -> Option<String> {
+        if hand.is_empty() {
+            return None;
+        }
+        let terms = hand
+            .entries
+            .iter()
+            .filter_map(|(index, count)| {
+                let name = self.dice.get(*index)?.get_name();
+                Some(if *count == 1 {
+                    name.to_string()
+                } else {
+                    format!("{}×{}", count, name)
+                })
+            })
+            .collect::<Vec<_>>();
+        Some(terms.join(" + "))
+    }
+
+    /// Rolls every die of `hand`, returning `None` for an empty hand.
+    pub(crate) fn roll_hand(&self, hand: &Hand, random: &mut Rand32) -> Option<HandRoll> {
+        let mut rolls = vec![];
+        for (index, count) in hand.entries.iter() {
+            if let Some(die) = self.dice.get(*index) {
+                for _ in 0..*count {
+                    rolls.push((*index, random.rand_range(die.get_range())));
+                }
+            }
+        }
+        (!rolls.is_empty()).then_some(HandRoll { rolls })
+    }
+
+    /// Applies modifier and target of `options` to the total of `roll`. A hand of one
+    /// die also detects criticals. Hands are rolled once, so the roll mode is dropped.
+    pub(crate) fn evaluate_hand_roll(&self, roll: &HandRoll, options: &RollOptions) -> RollResult {
+        let range = match roll.rolls.as_slice() {
+            [(index, _)] => self.dice.get(*index).map(Die::get_range),
+            _ => None,
+        };
+        let options = RollOptions {
+            mode: RollMode::Normal,
+            ..*options
+        };
+        options.evaluate(roll.total(), range)
+    }
+
+    /// Describes a hand roll as e.g. `D6: 3, D6: 5, D10: 7 (Total: 15)`.
+    pub(crate) fn describe_hand_roll(&self, roll: &HandRoll) -> String {
+        let faces = roll
+            .rolls
+            .iter()
+            .filter_map(|(index, value)| {
+                let die = self.dice.get(*index)?;
+                Some(format!("{}: {}", die.get_name(), value))
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{} ({})", faces, trf(Message::Total, &[&roll.total()]))
+    }
+
+    fn matching_dice(&self, predicate: impl Fn(&str) -> bool) -> Vec<usize> {
+        self.dice
+            .iter()
+            .enumerate()
+            .filter(|(_, die)| die.lookup_keys().iter().any(|key| predicate(key)))
+            .map(|(index, _)| index)
+            .collect()
+    }
 }
 
-impl<'a> Default for State<'a> {
-    fn default() -> Self {
-        Self { dice: vec![] }
+impl DieLookup {
+    fn from_candidates(mut candidates: Vec<usize>) -> Self {
+        if candidates.len() == 1 {
+            Self::Found(candidates.remove(0))
+        } else {
+            Self::Ambiguous(candidates)
+        }
     }
 }
 
-impl<'a> Display for State<'a> {
+impl Hand {
+    fn add(&mut self, index: usize, count: u32) {
+        if count == 0 {
+            return;
+        }
+        match self.entries.iter_mut().find(|(entry, _)| *entry == index) {
+            Some((_, existing)) => *existing = existing.saturating_add(count),
+            None => self.entries.push((index, count)),
+        }
+    }
+
+    fn remove(&mut self, index: usize) {
+        if let Some((_, count)) = self.entries.iter_mut().find(|(entry, _)| *entry == index) {
+            *count -= 1;
+        }
+        self.entries.retain(|(_, count)| *count > 0);
+    }
+
+    /// Drops the die at `index` and moves the dice after it one index down.
+    fn forget(&mut self, index: usize) {
+        self.entries.retain(|(entry, _)| *entry != index);
+        for (entry, _) in self.entries.iter_mut() {
+            if *entry > index {
+                *entry -= 1;
+            }
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of dice in the hand.
+    pub(crate) fn count(&self) -> u32 {
+        self.entries
+            .iter()
+            .fold(0, |total, (_, count)| total.saturating_add(*count))
+    }
+
+    /// Die index and count of every die in the hand.
+    pub(crate) fn get_entries(&self) -> &[(usize, u32)] {
+        &self.entries
+    }
+
+    /// Index of the die if the hand holds exactly one die.
+    pub(crate) fn single_die(&self) -> Option<usize> {
+        match self.entries.as_slice() {
+            [(index, 1)] => Some(*index),
+            _ => None,
+        }
+    }
+}
+
+impl HandRoll {
+    /// A roll of dice that were thrown elsewhere, like in the 3d scene.
+    pub(crate) fn new(rolls: Vec<(usize, u32)>) -> Self {
+        Self { rolls }
+    }
+
+    pub(crate) fn total(&self) -> u32 {
+        self.rolls.iter().map(|(_, value)| value).sum()
+    }
+
+    /// Die index and face of every rolled die.
+    pub(crate) fn get_rolls(&self) -> &[(usize, u32)] {
+        &self.rolls
+    }
+}
+
+/// Where the die at `index` ends up after the die at `from` moved to `to`.
+fn moved_index(index: usize, from: usize, to: usize) -> usize {
+    if index == from {
+        to
+    } else if from < index && index <= to {
+        index - 1
+    } else if to <= index && index < from {
+        index + 1
+    } else {
+        index
+    }
+}
+
+/// Levenshtein distance between two strings, counted in chars.
+fn edit_distance(left: &str, right: &str) -> usize {
+    let right: Vec<char> = right.chars().collect();
+    let mut previous: Vec<usize> = (0..=right.len()).collect();
+    for (i, left_char) in left.chars().enumerate() {
+        let mut current = vec![i + 1; right.len() + 1];
+        for (j, right_char) in right.iter().enumerate() {
+            let substitution = previous[j] + usize::from(left_char != *right_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[right.len()]
+}
+
+impl Display for State {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.dice.len() == 0 {
-            writeln!(f, "No dices configured!")?;
+        if self.dice.is_empty() {
+            writeln!(f, "{}", tr(Message::NoDiceConfigured))?;
         } else {
-            writeln!(f, "Outputting all currently configured dices.")?;
+            writeln!(f, "{}", tr(Message::AllConfiguredDice))?;
             for dice in self.dice.iter() {
                 write!(f, "{}", dice)?;
             }
@@ -49,20 +501,342 @@ impl<'a> Display for State<'a> {
     }
 }
 
-pub(crate) struct Die<'a> {
+/// Selected die, roll options and latest results of an interactive frontend.
+///
+/// The TUI and the GUI only translate their input into calls on the session and
+/// draw what it holds.
+pub(crate) struct Session {
+    state: State,
+    selected: Option<usize>,
+    roll_options: RollOptions,
+    random: Rand32,
+    /// Index of the rolled die and its result.
+    die_roll: Option<(usize, RollResult)>,
+    /// Latest result of every die, by die index.
+    last_rolls: Vec<Option<RollResult>>,
+    hand_roll: Option<(HandRoll, RollResult)>,
+}
+
+/// Everything of a [`Session`] worth keeping between launches, without the random
+/// number generator.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SavedSession {
+    state: State,
+    selected: Option<usize>,
+    roll_options: RollOptions,
+    die_roll: Option<(usize, RollResult)>,
+    last_rolls: Vec<Option<RollResult>>,
+}
+
+impl Session {
+    pub(crate) fn new(
+        state: State,
+        selected: Option<usize>,
+        roll_mode: RollMode,
+        random: Rand32,
+    ) -> Self {
+        let selected = selected.filter(|index| *index < state.dice.len());
+        Self {
+            state,
+            selected,
+            roll_options: RollOptions {
+                mode: roll_mode,
+                ..Default::default()
+            },
+            random,
+            die_roll: None,
+            last_rolls: vec![],
+            hand_roll: None,
+        }
+    }
+
+    /// Continues a saved session, dropping selections and results of dice it no longer has.
+    /// Dice that would not pass [`State::import_dice`] start the session over with the
+    /// built-in dice.
+    pub(crate) fn restore(saved: SavedSession, random: Rand32) -> Self {
+        let Ok(mut state) = State::from_dice(saved.state.dice) else {
+            let mut state = State::default();
+            crate::setup_default_dice(&mut state);
+            let mut session = Session::new(state, None, RollMode::Normal, random);
+            session.roll_options = saved.roll_options;
+            return session;
+        };
+        let count = state.dice.len();
+        state.hand = saved.state.hand;
+        state
+            .hand
+            .entries
+            .retain(|(index, dice)| *index < count && *dice > 0);
+        if state.hand.count() > MAX_HAND_DICE {
+            state.hand.entries.clear();
+        }
+        let mut last_rolls = saved.last_rolls;
+        last_rolls.truncate(count);
+        Self {
+            selected: saved.selected.filter(|index| *index < count),
+            roll_options: saved.roll_options,
+            random,
+            die_roll: saved.die_roll.filter(|(index, _)| *index < count),
+            last_rolls,
+            hand_roll: None,
+            state,
+        }
+    }
+
+    pub(crate) fn save(&self) -> SavedSession {
+        SavedSession {
+            state: self.state.clone(),
+            selected: self.selected,
+            roll_options: self.roll_options,
+            die_roll: self.die_roll,
+            last_rolls: self.last_rolls.clone(),
+        }
+    }
+
+    pub(crate) fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub(crate) fn random(&mut self) -> &mut Rand32 {
+        &mut self.random
+    }
+
+    pub(crate) fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub(crate) fn selected_die(&self) -> Option<&Die> {
+        self.selected.and_then(|index| self.state.dice.get(index))
+    }
+
+    /// Selects the die at `index`, ignoring indices past the last die.
+    pub(crate) fn select(&mut self, index: usize) {
+        if index < self.state.dice.len() {
+            self.selected = Some(index);
+        }
+    }
+
+    /// Selects the previous die, wrapping from the first to the last one.
+    /// Without a selection the last die is selected.
+    pub(crate) fn previous_die(&mut self) {
+        let count = self.state.dice.len();
+        if count == 0 {
+            return;
+        }
+        self.selected = Some(match self.selected {
+            Some(index) if index > 0 && index < count => index - 1,
+            _ => count - 1,
+        });
+    }
+
+    /// Selects the next die, wrapping from the last to the first one.
+    /// Without a selection the first die is selected.
+    pub(crate) fn next_die(&mut self) {
+        let count = self.state.dice.len();
+        if count == 0 {
+            return;
+        }
+        self.selected = Some(match self.selected {
+            Some(index) if index + 1 < count => index + 1,
+            _ => 0,
+        });
+    }
+
+    pub(crate) fn roll_options(&self) -> &RollOptions {
+        &self.roll_options
+    }
+
+    pub(crate) fn roll_options_mut(&mut self) -> &mut RollOptions {
+        &mut self.roll_options
+    }
+
+    /// Raises or lowers the modifier by `step`.
+    pub(crate) fn step_modifier(&mut self, step: i32) {
+        let options = &mut self.roll_options;
+        options.modifier = options.modifier.saturating_add(step);
+    }
+
+    /// Switches between normal rolls, advantage and disadvantage.
+    pub(crate) fn cycle_mode(&mut self) {
+        let options = &mut self.roll_options;
+        options.mode = options.mode.next();
+    }
+
+    /// Switches the target off, or on at [`DEFAULT_TARGET`].
+    pub(crate) fn toggle_target(&mut self) {
+        let options = &mut self.roll_options;
+        options.target = match options.target {
+            Some(_) => None,
+            None => Some(DEFAULT_TARGET),
+        };
+    }
+
+    /// Raises or lowers the target by `step` while it is switched on.
+    pub(crate) fn step_target(&mut self, step: i32) {
+        let options = &mut self.roll_options;
+        options.target = options.target.map(|target| target.saturating_add(step));
+    }
+
+    /// Rolls the selected die with the current roll options.
+    pub(crate) fn roll_die(&mut self) -> Option<RollResult> {
+        let index = self.selected?;
+        let result = self
+            .state
+            .dice
+            .get(index)?
+            .roll(&mut self.random, &self.roll_options);
+        self.die_roll = Some((index, result));
+        if self.last_rolls.len() <= index {
+            self.last_rolls.resize(index + 1, None);
+        }
+        self.last_rolls[index] = Some(result);
+        Some(result)
+    }
+
+    pub(crate) fn die_roll(&self) -> Option<(usize, RollResult)> {
+        self.die_roll
+    }
+
+    /// Latest result of the die at `index`, kept while other dice are rolled.
+    pub(crate) fn last_roll(&self, index: usize) -> Option<RollResult> {
+        self.last_rolls.get(index).copied().flatten()
+    }
+
+    /// Rolls every die of the hand and evaluates the total with the current roll options.
+    pub(crate) fn roll_hand(&mut self) -> Option<&(HandRoll, RollResult)> {
+        let roll = self.state.roll_hand(&self.state.hand, &mut self.random)?;
+        let result = self.state.evaluate_hand_roll(&roll, &self.roll_options);
+        self.hand_roll = Some((roll, result));
+        self.hand_roll.as_ref()
+    }
+
+    pub(crate) fn hand_roll(&self) -> Option<&(HandRoll, RollResult)> {
+        self.hand_roll.as_ref()
+    }
+
+    pub(crate) fn add_to_hand(&mut self) {
+        if let Some(index) = self.selected {
+            self.state.add_to_hand(index);
+        }
+    }
+
+    pub(crate) fn remove_from_hand(&mut self) {
+        if let Some(index) = self.selected {
+            self.state.remove_from_hand(index);
+        }
+    }
+
+    pub(crate) fn clear_hand(&mut self) {
+        self.state.clear_hand();
+        self.hand_roll = None;
+    }
+
+    /// Saves a new or changed die as [`State::save_die`] does and selects it.
+    pub(crate) fn save_die(
+        &mut self,
+        index: Option<usize>,
+        name: String,
+        faces: Vec<Symbol>,
+    ) -> usize {
+        let index = self.state.save_die(index, name, faces);
+        self.dice_changed();
+        if let Some(last_roll) = self.last_rolls.get_mut(index) {
+            *last_roll = None;
+        }
+        self.selected = Some(index);
+        index
+    }
+
+    /// Duplicates the selected die and selects the copy.
+    pub(crate) fn duplicate_die(&mut self) -> Option<&Die> {
+        let index = self.state.duplicate_die(self.selected?)?;
+        self.dice_changed();
+        self.selected = Some(index);
+        self.state.dice.get(index)
+    }
+
+    /// Removes the selected die and selects the one that took its place.
+    pub(crate) fn remove_die(&mut self) -> Option<Die> {
+        self.remove_die_at(self.selected?)
+    }
+
+    /// Removes the die at `index`. The selection stays on its die, or moves to the
+    /// die that took its place if the selected die was removed.
+    pub(crate) fn remove_die_at(&mut self, index: usize) -> Option<Die> {
+        let die = self.state.remove_die(index)?;
+        self.dice_changed();
+        if index < self.last_rolls.len() {
+            self.last_rolls.remove(index);
+        }
+        let last = self.state.dice.len().checked_sub(1);
+        self.selected = match self.selected {
+            Some(selected) if selected > index => Some(selected - 1),
+            Some(selected) if selected == index => last.map(|last| index.min(last)),
+            selected => selected,
+        };
+        Some(die)
+    }
+
+    /// Moves the die at `from` to `to`, keeping the selection and last results with their dice.
+    pub(crate) fn move_die(&mut self, from: usize, to: usize) {
+        if !self.state.move_die(from, to) {
+            return;
+        }
+        if self.last_rolls.len() < self.state.dice.len() {
+            self.last_rolls.resize(self.state.dice.len(), None);
+        }
+        let last_roll = self.last_rolls.remove(from);
+        self.last_rolls.insert(to, last_roll);
+        self.selected = self.selected.map(|index| moved_index(index, from, to));
+        self.dice_changed();
+    }
+
+    pub(crate) fn set_die_color(&mut self, index: usize, color: Option<[u8; 3]>) {
+        self.state.set_die_color(index, color);
+    }
+
+    /// Replaces the dice as [`State::import_dice`] does and selects the first one.
+    pub(crate) fn import_dice(&mut self, text: &str) -> Result<usize, String> {
+        let count = self.state.import_dice(text)?;
+        self.dice_changed();
+        self.last_rolls.clear();
+        self.selected = (count > 0).then_some(0);
+        Ok(count)
+    }
+
+    /// Drops results that refer to dice by index after dice were added, changed or removed.
+    fn dice_changed(&mut self) {
+        self.die_roll = None;
+        self.hand_roll = None;
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Die {
     name: String,
-    values: Vec<Symbol<'a>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
+    values: Vec<Symbol>,
+    /// Color the GUI draws the die in, as sRGB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color: Option<[u8; 3]>,
 }
 
-impl<'a> Die<'a> {
-    pub(crate) fn new(name: String) -> Die<'a> {
+impl Die {
+    pub(crate) fn new(name: String) -> Die {
         Self {
             name,
+            aliases: vec![],
             values: vec![],
+            color: None,
         }
     }
 
-    pub(crate) fn insert_symbol(&mut self, symbol: Symbol<'a>, index: usize) {
+    pub(crate) fn add_alias(&mut self, alias: String) {
+        self.aliases.push(alias);
+    }
+
+    pub(crate) fn insert_symbol(&mut self, symbol: Symbol, index: usize) {
         self.values.insert(index, symbol);
     }
 
@@ -83,11 +857,80 @@ impl<'a> Die<'a> {
     pub(crate) fn get_name(&self) -> &str {
         &self.name
     }
+
+    pub(crate) fn get_color(&self) -> Option<[u8; 3]> {
+        self.color
+    }
+
+    /// The faces, ordered by their number.
+    pub(crate) fn get_faces(&self) -> &[Symbol] {
+        &self.values
+    }
+
+    /// The face numbered `value`.
+    pub(crate) fn get_face(&self, value: u32) -> Option<&Symbol> {
+        self.values
+            .iter()
+            .find(|symbol| symbol.number as u32 == value)
+    }
+
+    /// `faces` with those this die already has replaced by its own, so that editing
+    /// the range keeps their names and colors.
+    pub(crate) fn keep_faces(&self, faces: Vec<Symbol>) -> Vec<Symbol> {
+        faces
+            .into_iter()
+            .map(|symbol| match self.get_face(symbol.number as u32) {
+                Some(own) => own.clone(),
+                None => symbol,
+            })
+            .collect()
+    }
+
+    /// The faces in the form accepted by [`Symbol::parse_faces`], e.g. `1-6`.
+    pub(crate) fn describe_faces(&self) -> String {
+        match (self.values.first(), self.values.last()) {
+            (Some(first), Some(last)) if first.number == last.number => first.number.to_string(),
+            (Some(first), Some(last)) => format!("{}-{}", first.number, last.number),
+            _ => String::new(),
+        }
+    }
+
+    /// Checks that the faces are distinct consecutive numbers, as rolling over
+    /// [`Die::get_range`] needs.
+    fn validate_faces(&self) -> Result<(), String> {
+        Symbol::validate_faces(&self.values)
+    }
+
+    /// Rolls the die, twice if `options` asks for advantage or disadvantage.
+    pub(crate) fn roll(&self, random: &mut Rand32, options: &RollOptions) -> RollResult {
+        let range = self.get_range();
+        let first = random.rand_range(range.clone());
+        match options.mode {
+            RollMode::Normal => options.evaluate(first, Some(range)),
+            RollMode::Advantage | RollMode::Disadvantage => {
+                let second = random.rand_range(range.clone());
+                options.evaluate_pair(first, second, range)
+            }
+        }
+    }
+
+    /// Lowercased name, explicit aliases and the number word of the face count,
+    /// both in English and in the active language.
+    fn lookup_keys(&self) -> Vec<String> {
+        let mut keys = vec![self.name.to_lowercase()];
+        keys.extend(self.aliases.iter().map(|alias| alias.to_lowercase()));
+        if let Some(symbol) = Symbol::COLLECTION.get(self.values.len()) {
+            keys.push(symbol.name.to_lowercase());
+            keys.push(symbol.get_name().to_lowercase());
+        }
+        keys.dedup();
+        keys
+    }
 }
 
-impl<'a> Display for Die<'a> {
+impl Display for Die {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Name: {}", self.name)?;
+        writeln!(f, "{}", trf(Message::DieName, &[&self.name]))?;
         for symbol in self.values.iter() {
             write!(f, "{}", symbol)?;
         }
@@ -95,22 +938,28 @@ impl<'a> Display for Die<'a> {
     }
 }
 
-#[derive(Clone)]
-pub(crate) struct Symbol<'a> {
-    name: &'a str,
+/// A face of a die. The built-in symbols borrow their English names, symbols
+/// loaded from a file own theirs.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Symbol {
+    name: Cow<'static, str>,
     number: usize,
+    /// Color the GUI draws this face in instead of the color of the die.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color: Option<[u8; 3]>,
 }
 
 macro_rules! CONST_SYMBOL {
     ($symbol: ident, $name:literal, $number:literal ) => {
         const $symbol: Self = Self {
-            name: $name,
+            name: Cow::Borrowed($name),
             number: $number,
+            color: None,
         };
     };
 }
 
-impl<'a> Symbol<'a> {
+impl Symbol {
     CONST_SYMBOL!(ZERO, "Zero", 0);
     CONST_SYMBOL!(ONE, "One", 1);
     CONST_SYMBOL!(TWO, "Two", 2);
@@ -126,14 +975,14 @@ impl<'a> Symbol<'a> {
     CONST_SYMBOL!(TWELVE, "Twelve", 12);
     CONST_SYMBOL!(THIRTEEN, "Thirteen", 13);
     CONST_SYMBOL!(FOURTEEN, "Fourteen", 14);
-    CONST_SYMBOL!(FIVETEEN, "Fiveteen", 15);
+    CONST_SYMBOL!(FIFTEEN, "Fifteen", 15);
     CONST_SYMBOL!(SIXTEEN, "Sixteen", 16);
     CONST_SYMBOL!(SEVENTEEN, "Seventeen", 17);
     CONST_SYMBOL!(EIGHTEEN, "Eighteen", 18);
     CONST_SYMBOL!(NINETEEN, "Nineteen", 19);
     CONST_SYMBOL!(TWENTY, "Twenty", 20);
 
-    pub(crate) const COLLECTION: [Symbol<'a>; 21] = [
+    pub(crate) const COLLECTION: [Symbol; 21] = [
         Symbol::ZERO,
         Symbol::ONE,
         Symbol::TWO,
@@ -149,18 +998,138 @@ impl<'a> Symbol<'a> {
         Symbol::TWELVE,
         Symbol::THIRTEEN,
         Symbol::FOURTEEN,
-        Symbol::FIVETEEN,
+        Symbol::FIFTEEN,
         Symbol::SIXTEEN,
         Symbol::SEVENTEEN,
         Symbol::EIGHTEEN,
         Symbol::NINETEEN,
         Symbol::TWENTY,
     ];
+
+    /// Highest number a face can have, that of the last built-in symbol.
+    pub(crate) const MAX_NUMBER: usize = Symbol::COLLECTION.len() - 1;
+
+    /// A face named `name`. Names of built-in symbols, in English or the active
+    /// language, give the built-in symbol so that it stays translated.
+    pub(crate) fn new(name: &str, number: usize, color: Option<[u8; 3]>) -> Symbol {
+        let name = name.trim();
+        let name = match Symbol::COLLECTION.get(number) {
+            Some(builtin) if builtin.name == name || builtin.get_name() == name => {
+                builtin.name.clone()
+            }
+            _ => Cow::Owned(name.to_string()),
+        };
+        Symbol {
+            name,
+            number,
+            color,
+        }
+    }
+
+    pub(crate) fn get_number(&self) -> usize {
+        self.number
+    }
+
+    pub(crate) fn get_color(&self) -> Option<[u8; 3]> {
+        self.color
+    }
+
+    /// Checks that `faces` are named, sorted and distinct consecutive numbers up to
+    /// [`Symbol::MAX_NUMBER`], as rolling over [`Die::get_range`] needs.
+    pub(crate) fn validate_faces(faces: &[Symbol]) -> Result<(), String> {
+        if faces.is_empty() {
+            return Err(tr(Message::EmptyFaces).to_string());
+        }
+        if let Some(unnamed) = faces.iter().find(|symbol| symbol.name.trim().is_empty()) {
+            return Err(trf(Message::EmptyFaceName, &[&unnamed.number]));
+        }
+        if let Some(high) = faces
+            .iter()
+            .find(|symbol| symbol.number > Symbol::MAX_NUMBER)
+        {
+            return Err(trf(
+                Message::FaceTooHigh,
+                &[&high.number, &Symbol::MAX_NUMBER],
+            ));
+        }
+        for pair in faces.windows(2) {
+            if pair[0].number == pair[1].number {
+                return Err(trf(Message::DuplicateFace, &[&pair[0].number]));
+            }
+            if pair[0].number + 1 != pair[1].number {
+                return Err(tr(Message::FacesNotConsecutive).to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses faces like `1-6`, `0, 1, 2` or `one two three` into built-in symbols.
+    ///
+    /// Dice are rolled over the range from their lowest to their highest face, so the
+    /// faces have to be distinct consecutive numbers from the collection.
+    pub(crate) fn parse_faces(input: &str) -> Result<Vec<Symbol>, String> {
+        let mut numbers = vec![];
+        for term in input
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|term| !term.is_empty())
+        {
+            match term.split_once('-') {
+                Some((first, last)) => {
+                    let first = Symbol::parse_face(first)?;
+                    let last = Symbol::parse_face(last)?;
+                    numbers.extend(first..=last);
+                }
+                None => numbers.push(Symbol::parse_face(term)?),
+            }
+        }
+        numbers.sort_unstable();
+        let faces = numbers
+            .into_iter()
+            .map(|number| Symbol::COLLECTION[number].clone())
+            .collect::<Vec<_>>();
+        Symbol::validate_faces(&faces)?;
+        Ok(faces)
+    }
+
+    /// Number of a built-in symbol given by its number or its English or localized name.
+    fn parse_face(term: &str) -> Result<usize, String> {
+        let term = term.trim();
+        if let Ok(number) = term.parse::<usize>() {
+            if number < Symbol::COLLECTION.len() {
+                return Ok(number);
+            }
+        }
+        Symbol::COLLECTION
+            .iter()
+            .find(|symbol| {
+                symbol.name.eq_ignore_ascii_case(term)
+                    || symbol.get_name().to_lowercase() == term.to_lowercase()
+            })
+            .map(|symbol| symbol.number)
+            .ok_or_else(|| trf(Message::UnknownFace, &[&term]))
+    }
+
+    /// Built-in symbols are named with the number word of the active language.
+    pub(crate) fn get_name(&self) -> &str {
+        match Symbol::COLLECTION.get(self.number) {
+            Some(builtin) if builtin.name == self.name => {
+                locale::number_word(self.number).unwrap_or(&self.name)
+            }
+            _ => &self.name,
+        }
+    }
 }
 
-impl<'a> Display for Symbol<'a> {
+impl Display for Symbol {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Symbol: {}, Number: {}", self.name, self.number)
+        writeln!(
+            f,
+            "{}",
+            trf(
+                Message::SymbolDescription,
+                &[&self.get_name(), &self.number]
+            )
+        )
     }
 }
 
@@ -189,3 +1158,440 @@ impl std::fmt::Debug for Error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// D4, D6, D10 and D20 with a fixed seed.
+    fn session(selected: Option<usize>) -> Session {
+        let mut state = State::default();
+        crate::setup_default_dice(&mut state);
+        Session::new(state, selected, RollMode::Normal, Rand32::new(7))
+    }
+
+    fn empty_session() -> Session {
+        Session::new(State::default(), None, RollMode::Normal, Rand32::new(7))
+    }
+
+    #[test]
+    fn empty_state_ignores_navigation_and_rolls() {
+        let mut session = empty_session();
+        session.previous_die();
+        session.next_die();
+        session.select(0);
+        assert_eq!(session.selected(), None);
+        assert!(session.roll_die().is_none());
+        assert!(session.roll_hand().is_none());
+        assert!(session.remove_die().is_none());
+        assert!(session.duplicate_die().is_none());
+    }
+
+    #[test]
+    fn next_die_wraps_to_the_first() {
+        let mut session = session(Some(2));
+        session.next_die();
+        assert_eq!(session.selected(), Some(3));
+        session.next_die();
+        assert_eq!(session.selected(), Some(0));
+    }
+
+    #[test]
+    fn previous_die_wraps_to_the_last() {
+        let mut session = session(Some(1));
+        session.previous_die();
+        assert_eq!(session.selected(), Some(0));
+        session.previous_die();
+        assert_eq!(session.selected(), Some(3));
+    }
+
+    #[test]
+    fn navigation_without_selection_starts_at_either_end() {
+        let mut session = session(None);
+        session.next_die();
+        assert_eq!(session.selected(), Some(0));
+        let mut session = self::session(None);
+        session.previous_die();
+        assert_eq!(session.selected(), Some(3));
+    }
+
+    #[test]
+    fn single_die_wraps_to_itself() {
+        let mut state = State::default();
+        let mut die = Die::new("D6".to_string());
+        die.values = Symbol::parse_faces("1-6").unwrap();
+        state.add_die(die);
+        let mut session = Session::new(state, Some(0), RollMode::Normal, Rand32::new(7));
+        session.next_die();
+        assert_eq!(session.selected(), Some(0));
+        session.previous_die();
+        assert_eq!(session.selected(), Some(0));
+    }
+
+    #[test]
+    fn out_of_range_selection_is_ignored() {
+        let mut session = session(Some(4));
+        assert_eq!(session.selected(), None);
+        session.select(1);
+        session.select(4);
+        assert_eq!(session.selected(), Some(1));
+    }
+
+    #[test]
+    fn roll_needs_a_selected_die() {
+        let mut session = session(None);
+        assert!(session.roll_die().is_none());
+        assert!(session.die_roll().is_none());
+    }
+
+    #[test]
+    fn roll_stays_within_the_faces() {
+        let mut session = session(Some(1));
+        for _ in 0..100 {
+            let result = session.roll_die().unwrap();
+            assert!((1..=6).contains(&result.natural()));
+        }
+        assert_eq!(session.die_roll().map(|(index, _)| index), Some(1));
+    }
+
+    #[test]
+    fn remove_die_selects_its_successor_and_fixes_the_hand() {
+        let mut session = session(Some(1));
+        session.add_to_hand();
+        session.select(3);
+        session.add_to_hand();
+        session.select(1);
+        session.roll_die();
+        let removed = session.remove_die().unwrap();
+        assert_eq!(removed.get_name(), "D6");
+        assert_eq!(session.selected_die().unwrap().get_name(), "D10");
+        assert_eq!(session.state().get_hand().entries, vec![(2, 1)]);
+        assert!(session.die_roll().is_none());
+    }
+
+    #[test]
+    fn remove_die_at_keeps_the_selection() {
+        let mut session = session(Some(2));
+        session.remove_die_at(3);
+        assert_eq!(session.selected_die().unwrap().get_name(), "D10");
+        session.remove_die_at(0);
+        assert_eq!(session.selected_die().unwrap().get_name(), "D10");
+        assert_eq!(session.selected(), Some(1));
+        session.remove_die_at(1);
+        assert_eq!(session.selected_die().unwrap().get_name(), "D6");
+        assert!(session.remove_die_at(5).is_none());
+    }
+
+    #[test]
+    fn faces_keep_their_names_values_and_colors() {
+        let faces = vec![
+            Symbol::new("Skull", 1, Some([0, 0, 0])),
+            Symbol::new("Two", 2, None),
+            Symbol::new(" Crown ", 3, Some([250, 200, 0])),
+        ];
+        assert_eq!(Symbol::validate_faces(&faces), Ok(()));
+        assert!(matches!(faces[1].name, Cow::Borrowed(_)));
+        let mut session = session(None);
+        let index = session.save_die(None, "D3".to_string(), faces);
+        let die = &session.state().get_dice()[index];
+        assert_eq!(die.get_range(), 1..4);
+        assert_eq!(die.get_face(3).unwrap().get_name(), "Crown");
+        assert_eq!(die.get_face(1).unwrap().get_color(), Some([0, 0, 0]));
+        let exported = session.state().export_dice().unwrap();
+        let mut other = empty_session();
+        assert_eq!(other.import_dice(&exported), Ok(5));
+        assert_eq!(other.state().get_dice(), session.state().get_dice());
+    }
+
+    #[test]
+    fn faces_need_names_and_consecutive_values() {
+        let unnamed = [Symbol::new("One", 1, None), Symbol::new(" ", 2, None)];
+        assert!(Symbol::validate_faces(&unnamed).is_err());
+        let gap = [Symbol::new("One", 1, None), Symbol::new("Three", 3, None)];
+        assert!(Symbol::validate_faces(&gap).is_err());
+        let twice = [Symbol::new("One", 1, None), Symbol::new("Uno", 1, None)];
+        assert!(Symbol::validate_faces(&twice).is_err());
+        assert!(Symbol::validate_faces(&[]).is_err());
+        let high = [
+            Symbol::new("Twenty", 20, None),
+            Symbol::new("Many", 21, None),
+        ];
+        assert!(Symbol::validate_faces(&high).is_err());
+    }
+
+    #[test]
+    fn faces_from_either_editor_fit_the_other() {
+        // The gui gives faces custom names, the tui edits their range as text.
+        let faces = vec![
+            Symbol::new("Skull", 19, Some([0, 0, 0])),
+            Symbol::new("Crown", 20, None),
+        ];
+        let mut session = session(None);
+        let index = session.save_die(None, "Custom".to_string(), faces);
+        let die = &session.state().get_dice()[index];
+        let parsed = Symbol::parse_faces(&die.describe_faces()).unwrap();
+        let kept = die.keep_faces(Symbol::parse_faces("18-20").unwrap());
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(Symbol::validate_faces(&kept), Ok(()));
+        assert_eq!(kept[0].get_name(), Symbol::EIGHTEEN.get_name());
+        assert_eq!(kept[1].get_name(), "Skull");
+        assert_eq!(kept[1].get_color(), Some([0, 0, 0]));
+        assert!(Symbol::parse_faces("20-21").is_err());
+    }
+
+    #[test]
+    fn last_rolls_follow_their_dice() {
+        let mut session = session(Some(1));
+        let natural =
+            |session: &Session, index| session.last_roll(index).map(|roll| roll.natural());
+        let d6 = session.roll_die().map(|roll| roll.natural());
+        session.select(3);
+        let d20 = session.roll_die().map(|roll| roll.natural());
+        assert_eq!(natural(&session, 1), d6);
+        session.select(0);
+        session.remove_die();
+        assert_eq!(natural(&session, 0), d6);
+        assert_eq!(natural(&session, 2), d20);
+        let faces = Symbol::parse_faces("1-12").unwrap();
+        session.save_die(Some(2), "D12".to_string(), faces);
+        assert_eq!(natural(&session, 2), None);
+    }
+
+    #[test]
+    fn remove_last_die_selects_the_new_last() {
+        let mut session = session(Some(3));
+        session.remove_die();
+        assert_eq!(session.selected(), Some(2));
+        for _ in 0..3 {
+            session.remove_die();
+        }
+        assert_eq!(session.selected(), None);
+        assert!(session.remove_die().is_none());
+    }
+
+    #[test]
+    fn duplicate_die_selects_the_copy() {
+        let mut session = session(Some(1));
+        assert_eq!(session.duplicate_die().unwrap().get_name(), "D6 (2)");
+        assert_eq!(session.selected(), Some(4));
+        session.select(1);
+        assert_eq!(session.duplicate_die().unwrap().get_name(), "D6 (3)");
+    }
+
+    #[test]
+    fn state_survives_a_toml_round_trip() {
+        let mut state = State::default();
+        crate::setup_default_dice(&mut state);
+        state.add_to_hand(1);
+        state.add_to_hand(1);
+        state.add_to_hand(3);
+        let text = toml::to_string(&state).unwrap();
+        let loaded: State = toml::from_str(&text).unwrap();
+        assert_eq!(loaded, state);
+        assert_eq!(loaded.get_dice()[2].lookup_keys()[1], "d%");
+    }
+
+    #[test]
+    fn move_die_keeps_selection_hand_and_results() {
+        let mut session = session(Some(0));
+        session.roll_die();
+        session.add_to_hand();
+        session.select(3);
+        session.add_to_hand();
+        session.move_die(0, 2);
+        let names = session
+            .state()
+            .get_dice()
+            .iter()
+            .map(|die| die.get_name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["D6", "D10", "D4", "D20"]);
+        assert_eq!(session.selected(), Some(3));
+        assert_eq!(session.state().get_hand().entries, vec![(2, 1), (3, 1)]);
+        assert!(session.last_roll(2).is_some());
+        assert!(session.last_roll(0).is_none());
+    }
+
+    #[test]
+    fn exported_dice_can_be_imported() {
+        let mut session = session(Some(1));
+        session.set_die_color(1, Some([200, 30, 30]));
+        let exported = session.state().export_dice().unwrap();
+        let mut other = empty_session();
+        assert_eq!(other.import_dice(&exported), Ok(4));
+        assert_eq!(other.state().get_dice(), session.state().get_dice());
+        assert_eq!(other.selected(), Some(0));
+    }
+
+    #[test]
+    fn invalid_import_keeps_the_dice() {
+        let mut session = session(Some(1));
+        let gap = "[[dice]]\nname = \"D3\"\nvalues = [{ name = \"One\", number = 1 }, { name = \"Three\", number = 3 }]\n";
+        assert!(session.import_dice(gap).is_err());
+        let duplicate = session.state().export_dice().unwrap().replace("D20", "D6");
+        assert!(session.import_dice(&duplicate).is_err());
+        assert!(session.import_dice("dice = 3").is_err());
+        assert_eq!(session.state().get_dice().len(), 4);
+        assert_eq!(session.selected(), Some(1));
+    }
+
+    #[test]
+    fn restored_session_keeps_dice_selection_and_results() {
+        let mut session = session(Some(0));
+        session.roll_options_mut().modifier = 3;
+        let rolled = session.roll_die().map(|roll| roll.natural());
+        session.select(2);
+        let text = toml::to_string(&session.save()).unwrap();
+        let restored = Session::restore(toml::from_str(&text).unwrap(), Rand32::new(1));
+        assert_eq!(restored.state(), session.state());
+        assert_eq!(restored.selected(), Some(2));
+        assert_eq!(restored.roll_options().modifier, 3);
+        assert_eq!(restored.last_roll(0).map(|roll| roll.natural()), rolled);
+    }
+
+    #[test]
+    fn restore_drops_selection_of_missing_dice() {
+        let mut session = session(Some(3));
+        session.roll_die();
+        let mut saved = session.save();
+        saved.state.dice.truncate(2);
+        let restored = Session::restore(saved, Rand32::new(1));
+        assert_eq!(restored.selected(), None);
+        assert!(restored.die_roll().is_none());
+        assert!(restored.last_roll(3).is_none());
+    }
+
+    #[test]
+    fn parse_roll_accepts_counts_and_modifiers() {
+        let session = session(None);
+        let (hand, options) = session.state().parse_roll("2xD6 + d10 + 3").unwrap();
+        assert_eq!(hand.entries, vec![(1, 2), (2, 1)]);
+        assert_eq!(options.modifier, 3);
+        let (hand, _) = session.state().parse_roll("2 d4, D4").unwrap();
+        assert_eq!(hand.entries, vec![(0, 3)]);
+        let (hand, _) = session.state().parse_roll("3*d20").unwrap();
+        assert_eq!(hand.single_die(), None);
+        assert_eq!(hand.count(), 3);
+    }
+
+    #[test]
+    fn parse_roll_finds_dice_named_like_a_target() {
+        let mut state = session(None).state().clone();
+        for name in ["Advs", "dcx"] {
+            let mut die = Die::new(name.to_string());
+            die.values = Symbol::parse_faces("1-6").unwrap();
+            state.add_die(die);
+        }
+        let (hand, options) = state.parse_roll("advs + 2 DCX vs DC 12").unwrap();
+        assert_eq!(hand.entries, vec![(4, 1), (5, 2)]);
+        assert_eq!(options.target, Some(12));
+    }
+
+    #[test]
+    fn hands_are_rolled_without_advantage() {
+        let state = session(None).state().clone();
+        assert!(state.parse_roll("2xd6 adv").is_err());
+        assert!(state.parse_roll("d6 + d4 dis").is_err());
+        let (_, options) = state.parse_roll("d20 adv").unwrap();
+        assert_eq!(options.mode, RollMode::Advantage);
+        let mut session = session(Some(1));
+        session.add_to_hand();
+        session.add_to_hand();
+        session.cycle_mode();
+        let (roll, result) = session.roll_hand().unwrap();
+        assert_eq!(result.faces(), vec![roll.total()]);
+        assert_eq!(result.to_string(), roll.total().to_string());
+    }
+
+    #[test]
+    fn parse_roll_rejects_bad_terms() {
+        let state = session(None).state().clone();
+        assert!(state.parse_roll("").is_err());
+        assert!(state.parse_roll("2x").is_err());
+        assert!(state.parse_roll("d7").is_err());
+        assert!(state.parse_roll("+ 3").is_err());
+    }
+
+    #[test]
+    fn parse_roll_rejects_zero_and_huge_counts() {
+        let state = session(None).state().clone();
+        assert!(state.parse_roll("0d6").is_err());
+        assert!(state.parse_roll("0d6 + d4").is_err());
+        assert!(state.parse_roll("4000000000d6").is_err());
+        assert!(state.parse_roll("99999999999d6").is_err());
+        assert!(state.parse_roll("60d6 + 60d4").is_err());
+        let (hand, _) = state.parse_roll(&format!("{}d6", MAX_HAND_DICE)).unwrap();
+        assert_eq!(hand.count(), MAX_HAND_DICE);
+    }
+
+    #[test]
+    fn hand_ignores_zero_counts_and_stops_at_the_limit() {
+        let mut hand = Hand::default();
+        hand.add(1, 0);
+        assert!(hand.is_empty());
+        let mut state = session(None).state().clone();
+        for _ in 0..=MAX_HAND_DICE {
+            state.add_to_hand(0);
+        }
+        assert_eq!(state.get_hand().count(), MAX_HAND_DICE);
+    }
+
+    #[test]
+    fn roll_options_are_stepped_and_toggled() {
+        let mut session = session(Some(3));
+        session.step_target(1);
+        assert_eq!(session.roll_options().target, None);
+        session.toggle_target();
+        session.step_target(2);
+        assert_eq!(session.roll_options().target, Some(DEFAULT_TARGET + 2));
+        session.toggle_target();
+        assert_eq!(session.roll_options().target, None);
+        session.step_modifier(-1);
+        assert_eq!(session.roll_options().modifier, -1);
+        session.roll_options_mut().modifier = i32::MAX;
+        session.step_modifier(1);
+        assert_eq!(session.roll_options().modifier, i32::MAX);
+        session.cycle_mode();
+        assert_eq!(session.roll_options().mode, RollMode::Advantage);
+    }
+
+    #[test]
+    fn restore_falls_back_to_the_built_in_dice() {
+        let mut session = session(Some(2));
+        session.set_die_color(0, Some([1, 2, 3]));
+        session.roll_options_mut().modifier = 2;
+        let mut saved = session.save();
+        saved.state.dice[1].values.clear();
+        let restored = Session::restore(saved, Rand32::new(1));
+        let mut built_in = State::default();
+        crate::setup_default_dice(&mut built_in);
+        assert_eq!(restored.state(), &built_in);
+        assert_eq!(restored.selected(), None);
+        assert_eq!(restored.roll_options().modifier, 2);
+        let mut saved = session.save();
+        saved.state.dice[3].name = "d6".to_string();
+        let restored = Session::restore(saved, Rand32::new(1));
+        assert_eq!(restored.state(), &built_in);
+    }
+
+    #[test]
+    fn restore_drops_hand_entries_of_missing_dice() {
+        let mut session = session(Some(3));
+        session.add_to_hand();
+        session.select(1);
+        session.add_to_hand();
+        let mut saved = session.save();
+        saved.state.dice.truncate(2);
+        saved.state.hand.entries.push((0, 0));
+        let restored = Session::restore(saved, Rand32::new(1));
+        assert_eq!(restored.state().get_hand().entries, vec![(1, 1)]);
+    }
+
+    #[test]
+    fn built_in_symbols_borrow_their_names() {
+        let faces = Symbol::parse_faces("1-6").unwrap();
+        assert!(faces
+            .iter()
+            .all(|symbol| matches!(symbol.name, Cow::Borrowed(_))));
+    }
+}
@@ -1,104 +1,818 @@
+use std::{f32::consts::TAU, path::Path};
+
 use eframe::egui::{self};
+use egui_plot::{Bar, BarChart, HLine, Legend, Line, Plot, PlotPoints};
 use oorandom::Rand32;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::{DieLookup, Error, SavedSession, Session, State},
+    history::{FairnessTest, History, MIN_EXPECTED_PER_FACE},
+    locale::{tr, trf, Message},
+    roll::{Outcome, RollMode, RollOptions, RollResult, DEFAULT_TARGET},
+};
 
-use crate::core::{Error, State};
+mod face;
+mod manager;
+
+use face::FaceTextures;
+use manager::DiceManager;
 
 const APPHEADING: &str = "wuerfel app";
+/// Smallest size of a die tile, wider rows stretch the tiles to fill the panel.
+const TILE_SIZE: egui::Vec2 = egui::vec2(96.0, 116.0);
+const PLOT_HEIGHT: f32 = 160.0;
+/// Window size at the first launch and after resetting to defaults.
+const WINDOW_SIZE: egui::Vec2 = egui::vec2(800.0, 600.0);
+/// Size of the die showing the current roll.
+const DIE_SIZE: f32 = 72.0;
+/// Seconds a rolled die spins before its result is revealed.
+const SPIN_DURATION: f64 = 0.6;
+/// Seconds between the random faces shown while spinning.
+const TUMBLE_STEP: f64 = 0.08;
+/// Full turns of a die at the start of its spin.
+const SPIN_TURNS: f32 = 2.0;
+/// Optional face images, see [`FaceTextures`].
+const FACES_DIRECTORY: &str = "assets/faces";
+/// Change of the font scale per click on the font size buttons.
+const ZOOM_STEP: f32 = 0.1;
+const MIN_ZOOM: f32 = 0.5;
+const MAX_ZOOM: f32 = 3.0;
 
-pub fn run_gui<'a>(state: State<'a>, random: Rand32) -> Result<(), Error> {
+pub fn run_gui(
+    state: State,
+    selected_die: Option<usize>,
+    roll_mode: RollMode,
+    random: Rand32,
+) -> Result<(), Error> {
     // env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
-    let range = 0..(state.get_dice().len() - 1);
-    let app = App {
-        state,
-        random,
-        current_index: None,
-        current_range: range,
-        current_die_roll: None,
-    };
+    let preselected = selected_die
+        .and_then(|index| state.get_dice().get(index))
+        .map(|die| die.get_name().to_string());
+    let mut app = App::new(Session::new(state, selected_die, roll_mode, random));
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_resizable(false)
-            .with_inner_size([280.0, 120.0]),
+            .with_inner_size(WINDOW_SIZE)
+            .with_min_inner_size([480.0, 360.0]),
         ..Default::default()
     };
     eframe::run_native(
         APPHEADING,
         options,
-        Box::new(|_| {
-            // This gives us image support:
-            //egui_extras::install_image_loaders(&cc.egui_ctx);
-
+        Box::new(move |cc| {
+            egui_extras::install_image_loaders(&cc.egui_ctx);
+            let saved = cc
+                .storage
+                .and_then(|storage| eframe::get_value::<Saved>(storage, eframe::APP_KEY));
+            if let Some(saved) = saved {
+                app.restore(saved, &cc.egui_ctx);
+                // A die or roll mode given on the command line wins over the saved one.
+                if let Some(name) = &preselected {
+                    if let DieLookup::Found(index) = app.session.state().find_die(name) {
+                        app.session.select(index);
+                    }
+                }
+                if roll_mode != RollMode::Normal {
+                    app.session.roll_options_mut().mode = roll_mode;
+                }
+            }
             Ok(Box::new(app))
         }),
     )
     .map_err(|error| error.into())
 }
 
-struct App<'a> {
-    state: State<'a>,
-    current_index: Option<usize>,
-    current_range: std::ops::Range<usize>,
-    current_die_roll: Option<u32>,
-    random: Rand32,
+/// What the GUI keeps in the eframe storage between launches. The window geometry
+/// is kept by eframe itself.
+#[derive(Serialize, Deserialize)]
+struct Saved {
+    session: SavedSession,
+    theme: egui::ThemePreference,
+    #[serde(default = "default_zoom")]
+    zoom: f32,
+    #[serde(default)]
+    high_contrast: bool,
+}
+
+fn default_zoom() -> f32 {
+    1.0
 }
 
-impl<'a> eframe::App for App<'a> {
+struct App {
+    session: Session,
+    search: String,
+    /// Lookup of the current search text, kept to show hints below the search box.
+    search_lookup: Option<DieLookup>,
+    /// Every roll of the session, the source of the statistics panel.
+    history: History,
+    spin: Option<Spin>,
+    textures: FaceTextures,
+    manager: DiceManager,
+    /// Theme chosen in the toolbar, kept here to be saved.
+    theme: egui::ThemePreference,
+    /// Font scale chosen in the toolbar or with Ctrl and +/-, kept to be saved as well.
+    zoom_factor: f32,
+    high_contrast: bool,
+}
+
+/// The last die roll of the session while it is still spinning.
+struct Spin {
+    die_index: usize,
+    /// Time the roll started, in seconds of [`egui::InputState::time`].
+    started: f64,
+    /// Random face shown while spinning.
+    face: u32,
+    next_face: f64,
+}
+
+impl Spin {
+    /// Turns left until the die rests upright, slowing down towards the end.
+    fn angle(&self, now: f64) -> f32 {
+        let progress = ((now - self.started) / SPIN_DURATION).clamp(0.0, 1.0) as f32;
+        SPIN_TURNS * TAU * (1.0 - progress).powi(3)
+    }
+}
+
+impl App {
+    fn new(session: Session) -> Self {
+        Self {
+            session,
+            search: String::new(),
+            search_lookup: None,
+            history: History::default(),
+            spin: None,
+            textures: FaceTextures::load(Path::new(FACES_DIRECTORY)),
+            manager: DiceManager::default(),
+            theme: egui::ThemePreference::System,
+            zoom_factor: 1.0,
+            high_contrast: false,
+        }
+    }
+
+    fn restore(&mut self, saved: Saved, ctx: &egui::Context) {
+        let random = self.fresh_random();
+        self.session = Session::restore(saved.session, random);
+        self.theme = saved.theme;
+        self.high_contrast = saved.high_contrast;
+        ctx.set_theme(saved.theme);
+        ctx.set_zoom_factor(saved.zoom.clamp(MIN_ZOOM, MAX_ZOOM));
+        self.apply_contrast(ctx);
+    }
+
+    /// Goes back to the built-in dice, the system theme, the normal font size and the
+    /// initial window size.
+    fn reset_to_defaults(&mut self, ctx: &egui::Context) {
+        let mut state = State::default();
+        crate::setup_default_dice(&mut state);
+        let random = self.fresh_random();
+        *self = App::new(Session::new(state, None, RollMode::Normal, random));
+        ctx.set_theme(self.theme);
+        ctx.set_zoom_factor(1.0);
+        self.apply_contrast(ctx);
+        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(WINDOW_SIZE));
+    }
+
+    /// Uses the high-contrast visuals for both themes while they are switched on.
+    fn apply_contrast(&self, ctx: &egui::Context) {
+        for theme in [egui::Theme::Dark, egui::Theme::Light] {
+            let visuals = if self.high_contrast {
+                high_contrast(theme)
+            } else {
+                theme.default_visuals()
+            };
+            ctx.set_visuals_of(theme, visuals);
+        }
+    }
+
+    /// The keys of the TUI: arrows and digits choose a die, Enter rolls it, Q or Escape
+    /// quits and the letters for the hand and the roll options do the same as there.
+    fn shortcuts(&mut self, ctx: &egui::Context) {
+        // Keys belong to the focused text field or button.
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let (events, now) = ctx.input(|input| (input.events.clone(), input.time));
+        for event in events {
+            match event {
+                egui::Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } if modifiers.is_none() => match key {
+                    egui::Key::ArrowLeft => self.session.previous_die(),
+                    egui::Key::ArrowRight => self.session.next_die(),
+                    egui::Key::Enter => self.roll_die(now),
+                    egui::Key::Escape if self.manager.open => self.manager.open = false,
+                    egui::Key::Escape => ctx.send_viewport_cmd(egui::ViewportCommand::Close),
+                    _ => {}
+                },
+                egui::Event::Text(text) => {
+                    for key in text.chars() {
+                        self.shortcut(ctx, key);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn shortcut(&mut self, ctx: &egui::Context, key: char) {
+        match key {
+            'q' | 'Q' => ctx.send_viewport_cmd(egui::ViewportCommand::Close),
+            digit @ '1'..='9' => self.session.select(digit as usize - '1' as usize),
+            'a' => self.session.add_to_hand(),
+            'd' => self.session.remove_from_hand(),
+            'r' => self.roll_hand(),
+            'x' => self.session.clear_hand(),
+            '+' => self.session.step_modifier(1),
+            '-' => self.session.step_modifier(-1),
+            'm' => self.session.cycle_mode(),
+            't' => self.session.toggle_target(),
+            '>' => self.session.step_target(1),
+            '<' => self.session.step_target(-1),
+            _ => {}
+        }
+    }
+
+    /// Buttons to scale all text and the high-contrast switch.
+    fn accessibility_ui(&mut self, ui: &mut egui::Ui) {
+        let ctx = ui.ctx().clone();
+        let zoom = ctx.zoom_factor();
+        ui.label(tr(Message::FontSize));
+        let smaller = ui
+            .add_enabled(zoom > MIN_ZOOM, egui::Button::new("A−"))
+            .on_hover_text(tr(Message::SmallerText));
+        smaller.widget_info(|| {
+            egui::WidgetInfo::labeled(
+                egui::WidgetType::Button,
+                zoom > MIN_ZOOM,
+                tr(Message::SmallerText),
+            )
+        });
+        if smaller.clicked() {
+            ctx.set_zoom_factor((zoom - ZOOM_STEP).max(MIN_ZOOM));
+        }
+        ui.label(format!("{:.0} %", zoom * 100.0));
+        let larger = ui
+            .add_enabled(zoom < MAX_ZOOM, egui::Button::new("A+"))
+            .on_hover_text(tr(Message::LargerText));
+        larger.widget_info(|| {
+            egui::WidgetInfo::labeled(
+                egui::WidgetType::Button,
+                zoom < MAX_ZOOM,
+                tr(Message::LargerText),
+            )
+        });
+        if larger.clicked() {
+            ctx.set_zoom_factor((zoom + ZOOM_STEP).min(MAX_ZOOM));
+        }
+        if ui
+            .checkbox(&mut self.high_contrast, tr(Message::HighContrast))
+            .changed()
+        {
+            self.apply_contrast(&ctx);
+        }
+    }
+
+    /// A generator seeded from the current one, for a session that replaces it.
+    fn fresh_random(&mut self) -> Rand32 {
+        let random = self.session.random();
+        Rand32::new((random.rand_u32() as u64) << 32 | random.rand_u32() as u64)
+    }
+
+    fn search_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|hui| {
+            hui.label(tr(Message::Search));
+            if hui.text_edit_singleline(&mut self.search).changed() {
+                let lookup = self.session.state().find_die(&self.search);
+                if let DieLookup::Found(index) = lookup {
+                    self.session.select(index);
+                }
+                self.search_lookup = Some(lookup).filter(|_| !self.search.trim().is_empty());
+            }
+        });
+        match &self.search_lookup {
+            Some(DieLookup::NotFound {
+                suggestion: Some(index),
+            }) => {
+                let index = *index;
+                if let Some(die) = self.session.state().get_dice().get(index) {
+                    if ui
+                        .link(trf(Message::DidYouMean, &[&die.get_name()]))
+                        .clicked()
+                    {
+                        self.search = die.get_name().to_string();
+                        self.session.select(index);
+                        self.search_lookup = None;
+                    }
+                }
+            }
+            Some(lookup @ (DieLookup::Ambiguous(_) | DieLookup::NotFound { .. })) => {
+                ui.label(self.session.state().describe_lookup(lookup));
+            }
+            Some(DieLookup::Found(_)) | None => {}
+        }
+    }
+
+    /// Rolls the selected die and starts its spin, the result is recorded once it settles.
+    fn roll_die(&mut self, now: f64) {
+        self.settle_spin();
+        if self.session.roll_die().is_none() {
+            return;
+        }
+        let (Some(index), Some(range)) = (
+            self.session.selected(),
+            self.session.selected_die().map(|die| die.get_range()),
+        ) else {
+            return;
+        };
+        self.spin = Some(Spin {
+            die_index: index,
+            started: now,
+            face: self.session.random().rand_range(range),
+            next_face: now + TUMBLE_STEP,
+        });
+    }
+
+    /// Shows a new random face every [`TUMBLE_STEP`] and settles the spin when it is over.
+    fn animate(&mut self, ctx: &egui::Context) {
+        let Some(spin) = self.spin.as_mut() else {
+            return;
+        };
+        let now = ctx.input(|input| input.time);
+        if now - spin.started >= SPIN_DURATION {
+            self.settle_spin();
+            return;
+        }
+        if now >= spin.next_face {
+            if let Some(die) = self.session.state().get_dice().get(spin.die_index) {
+                let range = die.get_range();
+                spin.face = self.session.random().rand_range(range);
+            }
+            spin.next_face = now + TUMBLE_STEP;
+        }
+        ctx.request_repaint();
+    }
+
+    /// Reveals the result of a spinning die and records it.
+    fn settle_spin(&mut self) {
+        if self.spin.take().is_none() {
+            return;
+        }
+        let Some((index, result)) = self.session.die_roll() else {
+            return;
+        };
+        if let Some(die) = self.session.state().get_dice().get(index) {
+            self.history.record_roll(die.get_name(), &result);
+        }
+    }
+
+    /// Face, angle and whether the die at `index` is still spinning, or `None` if it
+    /// was not rolled yet.
+    fn shown_face(&self, index: usize, now: f64) -> Option<(u32, f32, bool)> {
+        match &self.spin {
+            Some(spin) if spin.die_index == index => Some((spin.face, spin.angle(now), true)),
+            _ => self
+                .session
+                .last_roll(index)
+                .map(|result| (result.natural(), 0.0, false)),
+        }
+    }
+
+    fn roll_hand(&mut self) {
+        if self.session.roll_hand().is_none() {
+            return;
+        }
+        let Some((roll, _)) = self.session.hand_roll() else {
+            return;
+        };
+        let dice = self.session.state().get_dice();
+        for (index, value) in roll.get_rolls() {
+            if let Some(die) = dice.get(*index) {
+                self.history.record(die.get_name(), *value);
+            }
+        }
+    }
+
+    /// Face frequencies of the selected die against a fair die, its running mean and
+    /// a chi-square test of its fairness.
+    fn statistics_ui(&self, ui: &mut egui::Ui) {
+        ui.heading(tr(Message::Statistics));
+        let Some(die) = self.session.selected_die() else {
+            ui.label(tr(Message::NoDieSelected));
+            return;
+        };
+        let faces = die.get_range();
+        let counts = self.history.face_counts(die.get_name(), faces.clone());
+        let total = counts.iter().sum::<u64>();
+        let first = faces.start as f64;
+        let last = faces.end.saturating_sub(1) as f64;
+
+        ui.label(trf(Message::Histogram, &[&die.get_name()]));
+        let expected = total as f64 / counts.len().max(1) as f64;
+        let bars = faces
+            .clone()
+            .zip(counts.iter())
+            .map(|(face, count)| Bar::new(face as f64, *count as f64).width(0.8))
+            .collect();
+        let uniform = PlotPoints::from(vec![[first - 0.5, expected], [last + 0.5, expected]]);
+        Plot::new("face_frequencies")
+            .height(PLOT_HEIGHT)
+            .legend(Legend::default())
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .include_x(first - 0.5)
+            .include_x(last + 0.5)
+            .include_y(0.0)
+            .include_y(1.0)
+            .show(ui, |plot_ui| {
+                plot_ui.bar_chart(BarChart::new(bars).name(tr(Message::Rolled)));
+                plot_ui.line(Line::new(uniform).name(tr(Message::Expected)));
+            });
+
+        let rolls = self.history.roll_count(die.get_name());
+        ui.label(format!("{}: {}", tr(Message::Count), rolls));
+        let expected_mean = (first + last) / 2.0;
+        let running_mean = self.history.running_mean(die.get_name());
+        match running_mean.last() {
+            Some(mean) => ui.label(format!("{}: {:.2}", tr(Message::Mean), mean)),
+            None => ui.label(tr(Message::NoRolls)),
+        };
+        ui.label(trf(
+            Message::ExpectedMean,
+            &[&format!("{:.2}", expected_mean)],
+        ));
+        let points = running_mean
+            .iter()
+            .enumerate()
+            .map(|(index, mean)| [(index + 1) as f64, *mean])
+            .collect::<PlotPoints>();
+        Plot::new("running_mean")
+            .height(PLOT_HEIGHT)
+            .legend(Legend::default())
+            .allow_drag(false)
+            .allow_zoom(false)
+            .allow_scroll(false)
+            .include_x(1.0)
+            .include_y(first)
+            .include_y(last)
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new(points).name(tr(Message::RunningMean)));
+                plot_ui.hline(HLine::new(expected_mean).name(tr(Message::Expected)));
+            });
+
+        match FairnessTest::new(&counts) {
+            Some(test) => {
+                ui.label(trf(
+                    Message::ChiSquare,
+                    &[
+                        &format!("{:.2}", test.statistic),
+                        &format!("{:.2}", test.critical),
+                        &test.degrees_of_freedom,
+                    ],
+                ));
+                if test.looks_fair() {
+                    ui.colored_label(egui::Color32::DARK_GREEN, tr(Message::LooksFair));
+                } else {
+                    ui.colored_label(egui::Color32::RED, tr(Message::LooksUnfair));
+                }
+            }
+            None => {
+                let needed = MIN_EXPECTED_PER_FACE * counts.len() as u64;
+                ui.label(trf(Message::NotEnoughRolls, &[&needed]));
+            }
+        }
+    }
+
+    /// One clickable tile per die, reflowing into as many columns as fit the panel.
+    ///
+    /// Clicking a tile selects and rolls its die.
+    fn dice_grid_ui(&mut self, ui: &mut egui::Ui) {
+        let count = self.session.state().get_dice().len();
+        if count == 0 {
+            ui.label(tr(Message::NoDiceConfigured));
+            return;
+        }
+        let spacing = ui.spacing().item_spacing;
+        let available = ui.available_width();
+        let columns = (((available + spacing.x) / (TILE_SIZE.x + spacing.x)).floor() as usize)
+            .clamp(1, count);
+        let width = ((available - spacing.x * (columns - 1) as f32) / columns as f32)
+            .max(TILE_SIZE.x)
+            .floor();
+        let size = egui::vec2(width, TILE_SIZE.y);
+        for start in (0..count).step_by(columns) {
+            ui.horizontal(|hui| {
+                for index in start..(start + columns).min(count) {
+                    if self.die_tile(hui, index, size).clicked() {
+                        self.session.select(index);
+                        self.roll_die(hui.input(|input| input.time));
+                    }
+                }
+            });
+        }
+    }
+
+    /// Name and last result of a die, framed like a selectable button.
+    fn die_tile(&self, ui: &mut egui::Ui, index: usize, size: egui::Vec2) -> egui::Response {
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
+        let Some(die) = self.session.state().get_dice().get(index) else {
+            return response;
+        };
+        let response = response.on_hover_text(tr(Message::RollDie));
+        let selected = self.session.selected() == Some(index);
+        let spinning = matches!(&self.spin, Some(spin) if spin.die_index == index);
+        let result = self.session.last_roll(index).filter(|_| !spinning);
+        response.widget_info(|| {
+            let label = match &result {
+                Some(result) => trf(Message::RolledOn, &[result, &die.get_name()]),
+                None => die.get_name().to_string(),
+            };
+            egui::WidgetInfo::selected(egui::WidgetType::SelectableLabel, true, selected, label)
+        });
+        if ui.is_rect_visible(rect) {
+            let visuals = ui.style().interact_selectable(&response, selected);
+            let painter = ui.painter();
+            painter.rect(
+                rect,
+                visuals.rounding,
+                visuals.weak_bg_fill,
+                visuals.bg_stroke,
+            );
+            painter.text(
+                rect.center_top() + egui::vec2(0.0, rect.height() * 0.16),
+                egui::Align2::CENTER_CENTER,
+                die.get_name(),
+                egui::TextStyle::Heading.resolve(ui.style()),
+                visuals.text_color(),
+            );
+            let now = ui.input(|input| input.time);
+            let face_rect = egui::Rect::from_center_size(
+                rect.center_top() + egui::vec2(0.0, rect.height() * 0.52),
+                egui::Vec2::splat(rect.height() * 0.4),
+            );
+            match self.shown_face(index, now) {
+                Some((face, angle, tumbling)) => {
+                    face::paint_die(ui, face_rect, die, face, angle, tumbling, &self.textures);
+                }
+                None => {
+                    painter.text(
+                        face_rect.center(),
+                        egui::Align2::CENTER_CENTER,
+                        "–",
+                        egui::TextStyle::Heading.resolve(ui.style()),
+                        ui.visuals().weak_text_color(),
+                    );
+                }
+            }
+            if let Some(result) = result {
+                painter.text(
+                    rect.center_top() + egui::vec2(0.0, rect.height() * 0.87),
+                    egui::Align2::CENTER_CENTER,
+                    result.to_string(),
+                    egui::TextStyle::Body.resolve(ui.style()),
+                    result_color(ui, &result),
+                );
+            }
+        }
+        response
+    }
+
+    /// The die of the current roll, spinning at first, with its result once it settled.
+    fn current_roll_ui(&self, ui: &mut egui::Ui) {
+        let Some((index, result)) = self.session.die_roll() else {
+            return;
+        };
+        let Some(die) = self.session.state().get_dice().get(index) else {
+            return;
+        };
+        let now = ui.input(|input| input.time);
+        ui.horizontal(|hui| {
+            let (rect, _) =
+                hui.allocate_exact_size(egui::Vec2::splat(DIE_SIZE), egui::Sense::hover());
+            let response = hui.interact(rect, hui.id().with("current_roll"), egui::Sense::hover());
+            if let Some((face, angle, tumbling)) = self.shown_face(index, now) {
+                face::paint_die(hui, rect, die, face, angle, tumbling, &self.textures);
+            }
+            // Screen readers announce the result once the die settled.
+            let label = match self.spin {
+                Some(_) => die.get_name().to_string(),
+                None => trf(Message::RolledOn, &[&result, &die.get_name()]),
+            };
+            response
+                .widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Label, true, &label));
+            hui.ctx().accesskit_node_builder(response.id, |node| {
+                node.set_live(egui::accesskit::Live::Polite);
+            });
+            if self.spin.is_none() {
+                hui.vertical(|vui| {
+                    vui.label(format!("{}:", tr(Message::CurrentRoll)));
+                    result_label(vui, &result);
+                });
+            }
+        });
+    }
+
+    fn hand_ui(&mut self, ui: &mut egui::Ui) {
+        if self.session.selected().is_some() {
+            ui.horizontal(|hui| {
+                if hui.button(tr(Message::AddToHand)).clicked() {
+                    self.session.add_to_hand();
+                }
+                if hui.button(tr(Message::RemoveFromHand)).clicked() {
+                    self.session.remove_from_hand();
+                }
+            });
+        }
+        let state = self.session.state();
+        if let Some(hand) = state.describe_hand(state.get_hand()) {
+            ui.label(format!("{}: {}", tr(Message::Hand), hand));
+            ui.horizontal(|hui| {
+                if hui.button(tr(Message::RollHand)).clicked() {
+                    self.roll_hand();
+                }
+                if hui.button(tr(Message::ClearHand)).clicked() {
+                    self.session.clear_hand();
+                }
+            });
+        }
+        if let Some((roll, result)) = self.session.hand_roll() {
+            ui.label(format!(
+                "{}: {}",
+                tr(Message::HandRoll),
+                self.session.state().describe_hand_roll(roll)
+            ));
+            if *self.session.roll_options() != RollOptions::default() {
+                result_label(ui, result);
+            }
+        }
+    }
+
+    fn roll_options_ui(&mut self, ui: &mut egui::Ui) {
+        let roll_options = self.session.roll_options_mut();
+        ui.horizontal(|hui| {
+            hui.label(tr(Message::Modifier));
+            hui.add(egui::DragValue::new(&mut roll_options.modifier));
+            let mut has_target = roll_options.target.is_some();
+            if hui.checkbox(&mut has_target, tr(Message::Target)).changed() {
+                roll_options.target = has_target.then_some(DEFAULT_TARGET);
+            }
+            if let Some(target) = roll_options.target.as_mut() {
+                hui.add(egui::DragValue::new(target));
+            }
+        });
+        ui.horizontal(|hui| {
+            hui.label(tr(Message::RollMode));
+            for mode in RollMode::ALL {
+                hui.selectable_value(&mut roll_options.mode, mode, mode.label());
+            }
+        });
+    }
+}
+
+/// Shows a roll colored by its verdict, or by a natural maximum or minimum without a target.
+fn result_label(ui: &mut egui::Ui, result: &RollResult) {
+    ui.colored_label(result_color(ui, result), result.to_string());
+}
+
+/// Visuals of `theme` in pure black and white with strong outlines and a saturated
+/// selection.
+fn high_contrast(theme: egui::Theme) -> egui::Visuals {
+    use egui::{Color32, Stroke};
+    let mut visuals = theme.default_visuals();
+    let (background, foreground, accent, link, error) = match theme {
+        egui::Theme::Dark => (
+            Color32::BLACK,
+            Color32::WHITE,
+            Color32::from_rgb(0, 70, 180),
+            Color32::from_rgb(90, 200, 255),
+            Color32::from_rgb(255, 90, 90),
+        ),
+        egui::Theme::Light => (
+            Color32::WHITE,
+            Color32::BLACK,
+            Color32::from_rgb(255, 230, 0),
+            Color32::from_rgb(0, 0, 200),
+            Color32::from_rgb(180, 0, 0),
+        ),
+    };
+    visuals.override_text_color = Some(foreground);
+    visuals.panel_fill = background;
+    visuals.window_fill = background;
+    visuals.extreme_bg_color = background;
+    visuals.faint_bg_color = background;
+    visuals.window_stroke = Stroke::new(2.0, foreground);
+    visuals.selection.bg_fill = accent;
+    visuals.selection.stroke = Stroke::new(2.0, foreground);
+    visuals.hyperlink_color = link;
+    visuals.error_fg_color = error;
+    let widgets = &mut visuals.widgets;
+    for widget in [
+        &mut widgets.noninteractive,
+        &mut widgets.inactive,
+        &mut widgets.hovered,
+        &mut widgets.active,
+        &mut widgets.open,
+    ] {
+        widget.bg_stroke = Stroke::new(widget.bg_stroke.width.max(1.5), foreground);
+        widget.fg_stroke.color = foreground;
+        widget.bg_fill = background;
+        widget.weak_bg_fill = background;
+    }
+    widgets.hovered.weak_bg_fill = accent;
+    widgets.active.weak_bg_fill = accent;
+    visuals
+}
+
+fn result_color(ui: &egui::Ui, result: &RollResult) -> egui::Color32 {
+    match result.outcome() {
+        Outcome::Success => egui::Color32::DARK_GREEN,
+        Outcome::Failure => egui::Color32::RED,
+        Outcome::Neutral => ui.visuals().text_color(),
+    }
+}
+
+impl eframe::App for App {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        let saved = Saved {
+            session: self.session.save(),
+            theme: self.theme,
+            zoom: self.zoom_factor,
+            high_contrast: self.high_contrast,
+        };
+        eframe::set_value(storage, eframe::APP_KEY, &saved);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        let has_dice = !self.current_range.is_empty();
+        self.animate(ctx);
+        self.shortcuts(ctx);
+        self.zoom_factor = ctx.zoom_factor();
+        egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
+            ui.horizontal_wrapped(|hui| {
+                hui.label(tr(Message::Theme));
+                egui::widgets::global_theme_preference_buttons(hui);
+                hui.separator();
+                self.accessibility_ui(hui);
+                hui.separator();
+                hui.menu_button(tr(Message::Shortcuts), |mui| {
+                    mui.label(tr(Message::ShortcutsHelp));
+                });
+                hui.separator();
+                if hui.button(tr(Message::ResetToDefaults)).clicked() {
+                    self.reset_to_defaults(hui.ctx());
+                }
+            });
+        });
+        self.theme = ctx.options(|options| options.theme_preference);
+        if self.manager.show(ctx, &mut self.session) {
+            // The spinning die may have moved or been removed.
+            self.spin = None;
+        }
+        egui::SidePanel::right("statistics")
+            .resizable(true)
+            .default_width(280.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| self.statistics_ui(ui));
+            });
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.vertical_centered(|ui| {
                 ui.heading(APPHEADING);
             });
-            ui.vertical(|ui| {
-                ui.label("Dice");
-                ui.horizontal(|hui| {
-                    if hui.button("Previous").clicked() && has_dice {
-                        if self.current_index.is_none() && self.current_range.len() != 0 {
-                            self.current_index = Some(self.current_range.end);
-                        } else {
-                            self.current_index = self.current_index.map(|index| {
-                                if index == 0 {
-                                    self.current_range.end
-                                } else {
-                                    index - 1
-                                }
-                            });
+            egui::ScrollArea::vertical()
+                .auto_shrink(false)
+                .show(ui, |ui| {
+                    self.search_ui(ui);
+                    ui.horizontal(|hui| {
+                        hui.label(tr(Message::Dice));
+                        if hui.button(tr(Message::ManageDice)).clicked() {
+                            self.manager.open = true;
                         }
-                    }
-                    if let Some(index) = self.current_index {
-                        if let Some(die) = self.state.get_dice().get(index) {
-                            hui.label(format!("Die: {}", die.get_name()));
+                    });
+                    self.dice_grid_ui(ui);
+                    ui.horizontal(|hui| {
+                        if hui.button(tr(Message::Previous)).clicked() {
+                            self.session.previous_die();
                         }
-                    } else {
-                        hui.label("No die selected");
-                    }
-                    if hui.button("Next").clicked() && has_dice {
-                        if self.current_index.is_none() && self.current_range.len() != 0 {
-                            self.current_index = Some(0);
-                        } else {
-                            let max_index = self.current_range.end;
-                            self.current_index = self.current_index.map(|index| {
-                                if index + 1 > max_index {
-                                    0
-                                } else {
-                                    index + 1
-                                }
-                            });
+                        match self.session.selected_die() {
+                            Some(die) => hui.label(trf(Message::Die, &[&die.get_name()])),
+                            None => hui.label(tr(Message::NoDieSelected)),
+                        };
+                        if hui.button(tr(Message::Next)).clicked() {
+                            self.session.next_die();
                         }
+                    });
+                    self.roll_options_ui(ui);
+                    if self.session.selected().is_some()
+                        && ui.button(tr(Message::RollDie)).clicked()
+                    {
+                        self.roll_die(ui.input(|input| input.time));
                     }
+                    self.current_roll_ui(ui);
+                    ui.separator();
+                    self.hand_ui(ui);
                 });
-                if let Some(index) = self.current_index {
-                    if ui.button("Roll die").clicked() {
-                        if let Some(die) = self.state.get_dice().get(index) {
-                            let die_range = die.get_range();
-                            self.current_die_roll = Some(self.random.rand_range(die_range));
-                        }
-                    }
-                }
-                if let Some(roll) = self.current_die_roll {
-                    ui.label(format!("Current roll: {}", roll.to_string()));
-                }
-            });
         });
     }
 }
@@ -0,0 +1,198 @@
+use std::{collections::HashMap, f32::consts::TAU, fs, path::Path};
+
+use eframe::egui::{
+    self, emath::Rot2, epaint::TextShape, Color32, FontId, Pos2, Rect, Shape, Stroke, Vec2,
+};
+
+use crate::core::{Die, Symbol};
+
+/// Pip positions of a six-sided die, in units of the face radius.
+const PIPS: [&[(f32, f32)]; 6] = [
+    &[(0.0, 0.0)],
+    &[(-0.34, -0.34), (0.34, 0.34)],
+    &[(-0.34, -0.34), (0.0, 0.0), (0.34, 0.34)],
+    &[(-0.34, -0.34), (0.34, -0.34), (-0.34, 0.34), (0.34, 0.34)],
+    &[
+        (-0.34, -0.34),
+        (0.34, -0.34),
+        (0.0, 0.0),
+        (-0.34, 0.34),
+        (0.34, 0.34),
+    ],
+    &[
+        (-0.34, -0.34),
+        (0.34, -0.34),
+        (-0.34, 0.0),
+        (0.34, 0.0),
+        (-0.34, 0.34),
+        (0.34, 0.34),
+    ],
+];
+
+/// Face images found as `d<faces>/<value>.png` below a directory, e.g.
+/// `assets/faces/d6/1.png`, drawn instead of the painted faces.
+#[derive(Default)]
+pub(super) struct FaceTextures {
+    uris: HashMap<(usize, u32), String>,
+}
+
+impl FaceTextures {
+    /// Collects the images below `directory`, which does not have to exist.
+    pub(super) fn load(directory: &Path) -> Self {
+        let mut uris = HashMap::new();
+        let Ok(sets) = fs::read_dir(directory) else {
+            return Self::default();
+        };
+        for set in sets.flatten() {
+            let faces = set.file_name().to_str().and_then(|name| {
+                name.strip_prefix(['d', 'D'])
+                    .and_then(|faces| faces.parse::<usize>().ok())
+            });
+            let (Some(faces), Ok(images)) = (faces, fs::read_dir(set.path())) else {
+                continue;
+            };
+            for image in images.flatten() {
+                let path = image.path();
+                if path.extension().and_then(|extension| extension.to_str()) != Some("png") {
+                    continue;
+                }
+                let value = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.parse::<u32>().ok());
+                if let (Some(value), Ok(path)) = (value, path.canonicalize()) {
+                    uris.insert((faces, value), format!("file://{}", path.display()));
+                }
+            }
+        }
+        Self { uris }
+    }
+
+    fn get(&self, faces: usize, value: u32) -> Option<&str> {
+        self.uris.get(&(faces, value)).map(String::as_str)
+    }
+}
+
+/// Draws the face showing `value` of `die` into `rect`, turned by `angle`, in the
+/// color of the face or else of the die if it has one. Tumbling faces are drawn in a weaker color.
+pub(super) fn paint_die(
+    ui: &egui::Ui,
+    rect: Rect,
+    die: &Die,
+    value: u32,
+    angle: f32,
+    tumbling: bool,
+    textures: &FaceTextures,
+) {
+    let visuals = ui.visuals();
+    let color = die
+        .get_face(value)
+        .and_then(Symbol::get_color)
+        .or(die.get_color());
+    let (fill, ink) = match color {
+        Some([r, g, b]) => {
+            let fill = Color32::from_rgb(r, g, b);
+            // Perceived brightness, so the ink stays readable on light and dark dice.
+            let light = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32 > 140.0;
+            let ink = if light {
+                Color32::BLACK
+            } else {
+                Color32::WHITE
+            };
+            (
+                fill,
+                if tumbling {
+                    ink.gamma_multiply(0.6)
+                } else {
+                    ink
+                },
+            )
+        }
+        None if tumbling => (visuals.extreme_bg_color, visuals.weak_text_color()),
+        None => (visuals.extreme_bg_color, visuals.strong_text_color()),
+    };
+    let faces = die.get_range().len();
+    match textures.get(faces, value) {
+        Some(uri) => egui::Image::new(uri)
+            .rotate(angle, Vec2::splat(0.5))
+            .tint(if tumbling { ink } else { Color32::WHITE })
+            .paint_at(ui, rect),
+        None => paint_face(ui.painter(), rect, faces, value, angle, ink, fill),
+    }
+}
+
+/// Paints the outline of the face with its number, or pips for six-sided dice.
+///
+/// Four-, ten- and twenty-sided dice get the shape of their face and every other die
+/// a square.
+fn paint_face(
+    painter: &egui::Painter,
+    rect: Rect,
+    faces: usize,
+    value: u32,
+    angle: f32,
+    ink: Color32,
+    fill: Color32,
+) {
+    let center = rect.center();
+    let radius = rect.size().min_elem() / 2.0;
+    let rotation = Rot2::from_angle(angle);
+    let point = |x: f32, y: f32| center + rotation * egui::vec2(x, y) * radius;
+    let stroke = Stroke::new((radius * 0.06).max(1.0), ink);
+    let outline = |points: Vec<Pos2>| Shape::convex_polygon(points, fill, stroke);
+    let regular = |sides: usize, size: f32| {
+        (0..sides)
+            .map(|side| {
+                let corner = Rot2::from_angle(TAU * side as f32 / sides as f32 - TAU / 4.0);
+                let offset = corner * egui::vec2(size, 0.0);
+                point(offset.x, offset.y)
+            })
+            .collect::<Vec<_>>()
+    };
+    let label_offset = match faces {
+        4 => {
+            painter.add(outline(regular(3, 0.95)));
+            0.2
+        }
+        6 if (1..=6).contains(&value) => {
+            painter.add(outline(square(&point)));
+            for (x, y) in PIPS[value as usize - 1] {
+                painter.circle_filled(point(*x, *y), radius * 0.11, ink);
+            }
+            return;
+        }
+        10 => {
+            painter.add(outline(vec![
+                point(0.0, -0.95),
+                point(0.85, 0.15),
+                point(0.0, 0.75),
+                point(-0.85, 0.15),
+            ]));
+            0.0
+        }
+        20 => {
+            painter.add(outline(regular(6, 0.95)));
+            painter.add(Shape::closed_line(regular(3, 0.62), stroke));
+            0.08
+        }
+        _ => {
+            painter.add(outline(square(&point)));
+            0.0
+        }
+    };
+    let size = if value < 10 { 0.55 } else { 0.42 };
+    let galley =
+        painter.layout_no_wrap(value.to_string(), FontId::proportional(radius * size), ink);
+    let text_center = point(0.0, label_offset);
+    let corner = text_center - rotation * (galley.size() / 2.0);
+    painter.add(TextShape::new(corner, galley, ink).with_angle(angle));
+}
+
+fn square(point: &impl Fn(f32, f32) -> Pos2) -> Vec<Pos2> {
+    vec![
+        point(-0.64, -0.64),
+        point(0.64, -0.64),
+        point(0.64, 0.64),
+        point(-0.64, 0.64),
+    ]
+}
@@ -0,0 +1,351 @@
+use std::fs;
+
+use eframe::egui;
+
+use crate::{
+    core::{Session, Symbol},
+    locale::{tr, trf, Message},
+};
+
+/// Color a die or face gets when its color is switched on.
+const DEFAULT_COLOR: [u8; 3] = [200, 60, 60];
+/// Height of the list of faces before it scrolls.
+const FACES_HEIGHT: f32 = 200.0;
+
+/// Window to add, remove, reorder and edit dice and to import or export them.
+#[derive(Default)]
+pub(super) struct DiceManager {
+    pub(super) open: bool,
+    draft: Option<Draft>,
+    /// Outcome of the last import or export.
+    feedback: Option<Result<String, String>>,
+}
+
+/// A new or changed die that is only saved once its name and faces are valid.
+struct Draft {
+    /// Die being edited, or `None` for a new die.
+    index: Option<usize>,
+    name: String,
+    /// Range or list of faces as typed, parsed with [`Symbol::parse_faces`] to fill in
+    /// `faces`.
+    range: String,
+    faces: Vec<FaceDraft>,
+    color: Option<[u8; 3]>,
+}
+
+/// Name, value and color of one face of a [`Draft`].
+struct FaceDraft {
+    name: String,
+    number: usize,
+    color: Option<[u8; 3]>,
+}
+
+/// What was clicked in the list of dice, applied once the list is drawn.
+enum Change {
+    Edit(usize),
+    Move(usize, usize),
+    Delete(usize),
+}
+
+impl DiceManager {
+    /// Shows the window while it is open. Returns whether the dice changed, which
+    /// invalidates anything the caller keeps per die index.
+    pub(super) fn show(&mut self, ctx: &egui::Context, session: &mut Session) -> bool {
+        let mut open = self.open;
+        let mut changed = false;
+        egui::Window::new(tr(Message::ManageDice))
+            .open(&mut open)
+            .default_width(380.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|hui| {
+                    if hui.button(tr(Message::NewDie)).clicked() {
+                        self.draft = Some(Draft {
+                            index: None,
+                            name: String::new(),
+                            range: "1-6".to_string(),
+                            faces: Symbol::COLLECTION[1..=6]
+                                .iter()
+                                .map(FaceDraft::from)
+                                .collect(),
+                            color: None,
+                        });
+                    }
+                    if hui.button(tr(Message::Import)).clicked() {
+                        changed |= self.import(session);
+                    }
+                    if hui.button(tr(Message::Export)).clicked() {
+                        self.export(session);
+                    }
+                });
+                match &self.feedback {
+                    Some(Ok(message)) => {
+                        ui.label(message);
+                    }
+                    Some(Err(error)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, error);
+                    }
+                    None => {}
+                }
+                ui.separator();
+                if let Some(change) = dice_list(ui, session) {
+                    changed |= self.apply(change, session);
+                }
+                if self.draft.is_some() {
+                    ui.separator();
+                    changed |= self.draft_ui(ui, session);
+                }
+            });
+        self.open = open;
+        changed
+    }
+
+    fn apply(&mut self, change: Change, session: &mut Session) -> bool {
+        match change {
+            Change::Edit(index) => {
+                self.draft = session.state().get_dice().get(index).map(|die| Draft {
+                    index: Some(index),
+                    name: die.get_name().to_string(),
+                    range: die.describe_faces(),
+                    faces: die.get_faces().iter().map(FaceDraft::from).collect(),
+                    color: die.get_color(),
+                });
+                false
+            }
+            Change::Move(from, to) => {
+                session.move_die(from, to);
+                // The draft refers to its die by index.
+                self.draft = None;
+                true
+            }
+            Change::Delete(index) => {
+                session.remove_die_at(index);
+                self.draft = None;
+                true
+            }
+        }
+    }
+
+    /// Name, faces and color of the draft, saved only while they are valid.
+    fn draft_ui(&mut self, ui: &mut egui::Ui, session: &mut Session) -> bool {
+        let Some(draft) = self.draft.as_mut() else {
+            return false;
+        };
+        ui.strong(match draft.index {
+            Some(_) => tr(Message::EditDie),
+            None => tr(Message::NewDie),
+        });
+        let mut range_error = None;
+        egui::Grid::new("die_draft").num_columns(2).show(ui, |ui| {
+            ui.label(tr(Message::Name));
+            ui.text_edit_singleline(&mut draft.name);
+            ui.end_row();
+            ui.label(tr(Message::Faces));
+            let range = ui
+                .text_edit_singleline(&mut draft.range)
+                .on_hover_text(tr(Message::FacesHint));
+            if range.changed() {
+                range_error = draft.fill_faces().err();
+            }
+            ui.end_row();
+            ui.label(tr(Message::Color));
+            color_ui(ui, &mut draft.color);
+            ui.end_row();
+        });
+        if let Some(error) = range_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        faces_ui(ui, &mut draft.faces);
+        let validated = session
+            .state()
+            .validate_die_name(&draft.name, draft.index)
+            .and_then(|name| {
+                let faces = draft.symbols();
+                Symbol::validate_faces(&faces)?;
+                Ok((name, faces))
+            });
+        if let Err(error) = &validated {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        let (save, cancel) = ui
+            .horizontal(|hui| {
+                let save = hui.add_enabled(validated.is_ok(), egui::Button::new(tr(Message::Save)));
+                (save.clicked(), hui.button(tr(Message::Cancel)).clicked())
+            })
+            .inner;
+        let saved = match (save, validated) {
+            (true, Ok((name, faces))) => {
+                let index = session.save_die(draft.index, name, faces);
+                session.set_die_color(index, draft.color);
+                true
+            }
+            _ => false,
+        };
+        if saved || cancel {
+            self.draft = None;
+        }
+        saved
+    }
+
+    fn import(&mut self, session: &mut Session) -> bool {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("TOML", &["toml"])
+            .pick_file()
+        else {
+            return false;
+        };
+        let imported = fs::read_to_string(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|text| session.import_dice(&text));
+        self.feedback = Some(match imported {
+            Ok(count) => Ok(trf(Message::DiceImported, &[&count, &path.display()])),
+            Err(error) => Err(trf(Message::ImportFailed, &[&path.display(), &error])),
+        });
+        self.draft = None;
+        self.feedback.as_ref().is_some_and(Result::is_ok)
+    }
+
+    fn export(&mut self, session: &Session) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("TOML", &["toml"])
+            .set_file_name("dice.toml")
+            .save_file()
+        else {
+            return;
+        };
+        let exported = session
+            .state()
+            .export_dice()
+            .and_then(|text| fs::write(&path, text).map_err(|error| error.to_string()));
+        self.feedback = Some(match exported {
+            Ok(()) => Ok(trf(Message::DiceExported, &[&path.display()])),
+            Err(error) => Err(trf(Message::ExportFailed, &[&path.display(), &error])),
+        });
+    }
+}
+
+impl Draft {
+    /// Replaces the faces with those of the typed range, keeping the names and colors
+    /// of faces whose value is still part of it.
+    fn fill_faces(&mut self) -> Result<(), String> {
+        let symbols = Symbol::parse_faces(&self.range)?;
+        let mut previous = std::mem::take(&mut self.faces);
+        self.faces = symbols
+            .iter()
+            .map(|symbol| {
+                match previous
+                    .iter()
+                    .position(|face| face.number == symbol.get_number())
+                {
+                    Some(position) => previous.swap_remove(position),
+                    None => FaceDraft::from(symbol),
+                }
+            })
+            .collect();
+        Ok(())
+    }
+
+    /// The faces as symbols, ordered by their value.
+    fn symbols(&self) -> Vec<Symbol> {
+        let mut symbols = self
+            .faces
+            .iter()
+            .map(|face| Symbol::new(&face.name, face.number, face.color))
+            .collect::<Vec<_>>();
+        symbols.sort_by_key(Symbol::get_number);
+        symbols
+    }
+}
+
+impl From<&Symbol> for FaceDraft {
+    fn from(symbol: &Symbol) -> Self {
+        Self {
+            name: symbol.get_name().to_string(),
+            number: symbol.get_number(),
+            color: symbol.get_color(),
+        }
+    }
+}
+
+/// A checkbox to give something a color and a picker for the color.
+fn color_ui(ui: &mut egui::Ui, color: &mut Option<[u8; 3]>) {
+    ui.horizontal(|hui| {
+        let mut colored = color.is_some();
+        if hui.checkbox(&mut colored, "").changed() {
+            *color = colored.then_some(DEFAULT_COLOR);
+        }
+        if let Some(color) = color.as_mut() {
+            hui.color_edit_button_srgb(color);
+        }
+    });
+}
+
+/// Value, name and color of every face of a draft.
+fn faces_ui(ui: &mut egui::Ui, faces: &mut [FaceDraft]) {
+    egui::ScrollArea::vertical()
+        .max_height(FACES_HEIGHT)
+        .show(ui, |ui| {
+            egui::Grid::new("draft_faces")
+                .num_columns(3)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong(tr(Message::Value));
+                    ui.strong(tr(Message::Name));
+                    ui.strong(tr(Message::Color));
+                    ui.end_row();
+                    for face in faces.iter_mut() {
+                        ui.add(
+                            egui::DragValue::new(&mut face.number).range(0..=Symbol::MAX_NUMBER),
+                        );
+                        ui.text_edit_singleline(&mut face.name);
+                        color_ui(ui, &mut face.color);
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+/// Every die with its color, name and faces and buttons to move, edit and delete it.
+fn dice_list(ui: &mut egui::Ui, session: &Session) -> Option<Change> {
+    let dice = session.state().get_dice();
+    if dice.is_empty() {
+        ui.label(tr(Message::NoDiceConfigured));
+        return None;
+    }
+    let mut change = None;
+    egui::Grid::new("dice_manager")
+        .num_columns(4)
+        .striped(true)
+        .show(ui, |ui| {
+            for (index, die) in dice.iter().enumerate() {
+                let (swatch, _) =
+                    ui.allocate_exact_size(egui::Vec2::splat(14.0), egui::Sense::hover());
+                if let Some([r, g, b]) = die.get_color() {
+                    ui.painter()
+                        .rect_filled(swatch, 3.0, egui::Color32::from_rgb(r, g, b));
+                }
+                ui.label(die.get_name());
+                ui.label(die.describe_faces());
+                ui.horizontal(|hui| {
+                    let up = hui.add_enabled(index > 0, egui::Button::new("⏶").small());
+                    if up.on_hover_text(tr(Message::MoveUp)).clicked() {
+                        change = Some(Change::Move(index, index - 1));
+                    }
+                    let down =
+                        hui.add_enabled(index + 1 < dice.len(), egui::Button::new("⏷").small());
+                    if down.on_hover_text(tr(Message::MoveDown)).clicked() {
+                        change = Some(Change::Move(index, index + 1));
+                    }
+                    let edit = hui.small_button("✏").on_hover_text(tr(Message::EditDie));
+                    if edit.clicked() {
+                        change = Some(Change::Edit(index));
+                    }
+                    let delete = hui.small_button("🗑").on_hover_text(tr(Message::DeleteDie));
+                    if delete.clicked() {
+                        change = Some(Change::Delete(index));
+                    }
+                });
+                ui.end_row();
+            }
+        });
+    change
+}
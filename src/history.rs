@@ -0,0 +1,267 @@
+use std::{
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+use crate::roll::RollResult;
+
+/// Every roll of the session, oldest first.
+pub(crate) struct History {
+    started: Instant,
+    entries: Vec<HistoryEntry>,
+}
+
+pub(crate) struct HistoryEntry {
+    die: String,
+    /// The kept face, which the result is based on.
+    value: u32,
+    /// Every face that came up, both of a roll with advantage or disadvantage.
+    faces: Vec<u32>,
+    /// Full result text, including modifier, verdict and the discarded face.
+    detail: String,
+    elapsed: Duration,
+}
+
+/// Count, mean and extremes of the kept faces rolled with one die.
+pub(crate) struct DieStatistics {
+    pub(crate) die: String,
+    pub(crate) count: usize,
+    pub(crate) mean: f64,
+    pub(crate) min: u32,
+    pub(crate) max: u32,
+}
+
+/// Pearson's chi-square test of face counts against a fair die.
+pub(crate) struct FairnessTest {
+    pub(crate) statistic: f64,
+    pub(crate) degrees_of_freedom: usize,
+    /// Value a fair die stays below in 95% of the sessions.
+    pub(crate) critical: f64,
+}
+
+/// Rolls expected per face before the chi-square approximation holds.
+pub(crate) const MIN_EXPECTED_PER_FACE: u64 = 5;
+
+impl History {
+    /// Records a single face `value` rolled with `die`.
+    pub(crate) fn record(&mut self, die: &str, value: u32) {
+        self.push(die, value, vec![value], value.to_string());
+    }
+
+    /// Records `result` of `die`. Only the kept face counts towards the statistics, but
+    /// every face counts towards the face counts, so that advantage does not skew them.
+    pub(crate) fn record_roll(&mut self, die: &str, result: &RollResult) {
+        self.push(die, result.natural(), result.faces(), result.to_string());
+    }
+
+    fn push(&mut self, die: &str, value: u32, faces: Vec<u32>, detail: String) {
+        self.entries.push(HistoryEntry {
+            die: die.to_string(),
+            value,
+            faces,
+            detail,
+            elapsed: self.started.elapsed(),
+        });
+    }
+
+    /// Number of results rolled with `die`, a roll with advantage counting once.
+    pub(crate) fn roll_count(&self, die: &str) -> usize {
+        self.entries.iter().filter(|entry| entry.die == die).count()
+    }
+
+    pub(crate) fn get_entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Every face rolled with `die`, oldest first.
+    fn faces_of<'a>(&'a self, die: &'a str) -> impl Iterator<Item = u32> + 'a {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.die == die)
+            .flat_map(|entry| entry.faces.iter().copied())
+    }
+
+    /// How often each face in `faces` was rolled with `die`.
+    pub(crate) fn face_counts(&self, die: &str, faces: Range<u32>) -> Vec<u64> {
+        let mut counts = vec![0; faces.len()];
+        for face in self.faces_of(die) {
+            if faces.contains(&face) {
+                counts[(face - faces.start) as usize] += 1;
+            }
+        }
+        counts
+    }
+
+    /// Mean of the faces of `die` after each face it rolled.
+    pub(crate) fn running_mean(&self, die: &str) -> Vec<f64> {
+        let mut sum = 0.0;
+        self.faces_of(die)
+            .enumerate()
+            .map(|(index, face)| {
+                sum += face as f64;
+                sum / (index + 1) as f64
+            })
+            .collect()
+    }
+
+    /// Statistics of the kept faces per die, in the order the dice were first rolled.
+    pub(crate) fn statistics(&self) -> Vec<DieStatistics> {
+        let mut statistics: Vec<DieStatistics> = vec![];
+        for entry in self.entries.iter() {
+            let value = entry.value;
+            match statistics.iter_mut().find(|stats| stats.die == entry.die) {
+                Some(stats) => {
+                    stats.mean =
+                        (stats.mean * stats.count as f64 + value as f64) / (stats.count + 1) as f64;
+                    stats.count += 1;
+                    stats.min = stats.min.min(value);
+                    stats.max = stats.max.max(value);
+                }
+                None => statistics.push(DieStatistics {
+                    die: entry.die.clone(),
+                    count: 1,
+                    mean: value as f64,
+                    min: value,
+                    max: value,
+                }),
+            }
+        }
+        statistics
+    }
+}
+
+impl FairnessTest {
+    /// Tests `counts` from [`History::face_counts`], or returns `None` for a single face
+    /// or while fewer than [`MIN_EXPECTED_PER_FACE`] rolls per face were made.
+    pub(crate) fn new(counts: &[u64]) -> Option<FairnessTest> {
+        let total = counts.iter().sum::<u64>();
+        if counts.len() < 2 || total < MIN_EXPECTED_PER_FACE * counts.len() as u64 {
+            return None;
+        }
+        let expected = total as f64 / counts.len() as f64;
+        let statistic = counts
+            .iter()
+            .map(|count| (*count as f64 - expected).powi(2) / expected)
+            .sum();
+        let degrees_of_freedom = counts.len() - 1;
+        Some(FairnessTest {
+            statistic,
+            degrees_of_freedom,
+            critical: chi_square_critical(degrees_of_freedom),
+        })
+    }
+
+    pub(crate) fn looks_fair(&self) -> bool {
+        self.statistic <= self.critical
+    }
+}
+
+/// 95th percentiles of the chi-square distribution for 1 to 20 degrees of freedom,
+/// enough for dice with up to 21 faces.
+const CHI_SQUARE_95: [f64; 20] = [
+    3.841, 5.991, 7.815, 9.488, 11.070, 12.592, 14.067, 15.507, 16.919, 18.307, 19.675, 21.026,
+    22.362, 23.685, 24.996, 26.296, 27.587, 28.869, 30.144, 31.410,
+];
+
+/// 95th percentile from the table, approximated after Wilson and Hilferty beyond it.
+fn chi_square_critical(degrees_of_freedom: usize) -> f64 {
+    if let Some(critical) = CHI_SQUARE_95.get(degrees_of_freedom.wrapping_sub(1)) {
+        return *critical;
+    }
+    const Z_95: f64 = 1.644_854;
+    let k = degrees_of_freedom as f64;
+    let variance = 2.0 / (9.0 * k);
+    k * (1.0 - variance + Z_95 * variance.sqrt()).powi(3)
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            entries: vec![],
+        }
+    }
+}
+
+impl HistoryEntry {
+    pub(crate) fn get_die(&self) -> &str {
+        &self.die
+    }
+
+    pub(crate) fn get_detail(&self) -> &str {
+        &self.detail
+    }
+
+    /// Time since the start of the session as `hh:mm:ss`.
+    pub(crate) fn format_time(&self) -> String {
+        let seconds = self.elapsed.as_secs();
+        format!(
+            "{:02}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::roll::{RollMode, RollOptions};
+
+    #[test]
+    fn fairness_test_needs_enough_rolls() {
+        assert!(FairnessTest::new(&[4, 5, 5, 5, 5, 5]).is_none());
+        assert!(FairnessTest::new(&[100]).is_none());
+        assert!(FairnessTest::new(&[5, 5, 5, 5, 5, 5]).is_some());
+    }
+
+    #[test]
+    fn fairness_test_flags_skewed_counts() {
+        let even = FairnessTest::new(&[10, 11, 9, 10, 12, 8]).unwrap();
+        assert!(even.looks_fair());
+        let skewed = FairnessTest::new(&[30, 6, 6, 6, 6, 6]).unwrap();
+        assert!(!skewed.looks_fair());
+    }
+
+    #[test]
+    fn critical_values_extend_the_table() {
+        assert_eq!(chi_square_critical(5), 11.070);
+        // The approximation continues the table smoothly.
+        let beyond = chi_square_critical(21);
+        assert!((beyond - 32.671).abs() < 0.05, "{}", beyond);
+    }
+
+    #[test]
+    fn running_mean_only_counts_the_die() {
+        let mut history = History::default();
+        for (die, value) in [("D6", 2), ("D20", 20), ("D6", 4), ("D6", 6)] {
+            history.record(die, value);
+        }
+        assert_eq!(history.running_mean("D6"), vec![2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn both_faces_of_a_pair_count() {
+        let mut history = History::default();
+        let advantage = RollOptions {
+            mode: RollMode::Advantage,
+            ..RollOptions::default()
+        };
+        history.record_roll("D20", &advantage.evaluate_pair(3, 17, 1..21));
+        history.record("D20", 12);
+        let counts = history.face_counts("D20", 1..21);
+        assert_eq!((counts[2], counts[11], counts[16]), (1, 1, 1));
+        assert_eq!(history.running_mean("D20"), vec![3.0, 10.0, 32.0 / 3.0]);
+        // The statistics only count the kept face.
+        assert_eq!(history.roll_count("D20"), 2);
+        let statistics = history.statistics();
+        assert_eq!(statistics[0].count, 2);
+        assert_eq!(statistics[0].mean, 14.5);
+        assert_eq!((statistics[0].min, statistics[0].max), (12, 17));
+    }
+}
@@ -0,0 +1,589 @@
+use std::{fmt::Display, sync::OnceLock};
+
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    /// Parses a language code like `de`, `en_US.UTF-8` or `german`.
+    pub(crate) fn parse(code: &str) -> Option<Language> {
+        let code = code.trim().to_lowercase();
+        let primary = code.split(['_', '-', '.', '@']).next().unwrap_or_default();
+        match primary {
+            "de" | "german" | "deutsch" => Some(Language::German),
+            "en" | "english" | "englisch" | "c" | "posix" => Some(Language::English),
+            _ => None,
+        }
+    }
+
+    /// Detects the language from the usual POSIX locale variables.
+    pub(crate) fn detect() -> Language {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Language::parse(&value))
+            .unwrap_or_default()
+    }
+}
+
+/// Sets the language for the rest of the process. Only the first call has an effect.
+pub(crate) fn set_language(language: Language) {
+    let _ = LANGUAGE.set(language);
+}
+
+pub(crate) fn language() -> Language {
+    LANGUAGE.get().copied().unwrap_or_default()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Message {
+    Help,
+    MissingDieName,
+    UnknownLanguage,
+    NoDiceConfigured,
+    AllConfiguredDice,
+    DieName,
+    SymbolDescription,
+    LookupFound,
+    LookupAmbiguous,
+    LookupSuggestion,
+    LookupNotFound,
+    None,
+    TryAgain,
+    RollAgain,
+    InvalidYesNo,
+    YouSelected,
+    ThrowingDie,
+    YouRolled,
+    AvailableDice,
+    AppTitle,
+    Previous,
+    Next,
+    RollDie,
+    Search,
+    Select,
+    Cancel,
+    Quit,
+    Dice,
+    SelectedDie,
+    CurrentRoll,
+    NoDieSelected,
+    Die,
+    DidYouMean,
+    JumpHint,
+    InvalidCount,
+    MissingDieInTerm,
+    EmptyHand,
+    EnterHand,
+    EnterRollOptions,
+    ModeNeedsSingleDie,
+    UnexpectedDice,
+    Hand,
+    Total,
+    AddToHand,
+    RemoveFromHand,
+    ClearHand,
+    RollHand,
+    HandRoll,
+    InvalidTarget,
+    ModifierTooLarge,
+    NegativeDie,
+    Success,
+    Failure,
+    CriticalMax,
+    CriticalMin,
+    Modifier,
+    Target,
+    RollMode,
+    Normal,
+    Advantage,
+    Disadvantage,
+    History,
+    Statistics,
+    NoRolls,
+    DieHeader,
+    Count,
+    Mean,
+    Min,
+    Max,
+    Scroll,
+    CopyEntry,
+    ClearHistory,
+    Copied,
+    CopyFailed,
+    Histogram,
+    Rolled,
+    Expected,
+    Simulate,
+    SimulationStarted,
+    SimulationFinished,
+    InvalidTickRate,
+    EmptyDieName,
+    DuplicateDieName,
+    EmptyFaces,
+    UnknownFace,
+    DuplicateFace,
+    FacesNotConsecutive,
+    FaceTooHigh,
+    NewDie,
+    EditDie,
+    DuplicateDie,
+    DeleteDie,
+    Name,
+    Faces,
+    FacesHint,
+    Value,
+    EmptyFaceName,
+    Save,
+    NextField,
+    DieSaved,
+    DieDeleted,
+    MissingConfigPath,
+    ConfigError,
+    UnknownAction,
+    UnknownKey,
+    UnknownPreset,
+    UnknownColor,
+    UnknownBorder,
+    RunningMean,
+    ExpectedMean,
+    ChiSquare,
+    LooksFair,
+    LooksUnfair,
+    NotEnoughRolls,
+    InvalidDie,
+    ManageDice,
+    MoveUp,
+    MoveDown,
+    Color,
+    Import,
+    Export,
+    DiceImported,
+    DiceExported,
+    ImportFailed,
+    ExportFailed,
+    Theme,
+    ResetToDefaults,
+    RolledOn,
+    FontSize,
+    SmallerText,
+    LargerText,
+    HighContrast,
+    Shortcuts,
+    ShortcutsHelp,
+    Rolling,
+    InvalidThrowStrength,
+    NoShape,
+    InvalidHand,
+    TooManyDice,
+}
+
+/// Returns the template of `message` in the active language.
+pub(crate) fn tr(message: Message) -> &'static str {
+    match language() {
+        Language::English => english(message),
+        Language::German => german(message),
+    }
+}
+
+/// Returns `message` with each `{}` replaced by the next argument.
+pub(crate) fn trf(message: Message, args: &[&dyn Display]) -> String {
+    let mut args = args.iter();
+    let mut parts = tr(message).split("{}");
+    let mut buffer = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            buffer.push_str(&arg.to_string());
+        }
+        buffer.push_str(part);
+    }
+    buffer
+}
+
+/// Localized word for `number`, used for the names of the built-in symbols.
+pub(crate) fn number_word(number: usize) -> Option<&'static str> {
+    const ENGLISH: [&str; 21] = [
+        "Zero",
+        "One",
+        "Two",
+        "Three",
+        "Four",
+        "Five",
+        "Six",
+        "Seven",
+        "Eight",
+        "Nine",
+        "Ten",
+        "Eleven",
+        "Twelve",
+        "Thirteen",
+        "Fourteen",
+        "Fifteen",
+        "Sixteen",
+        "Seventeen",
+        "Eighteen",
+        "Nineteen",
+        "Twenty",
+    ];
+    const GERMAN: [&str; 21] = [
+        "Null",
+        "Eins",
+        "Zwei",
+        "Drei",
+        "Vier",
+        "Fünf",
+        "Sechs",
+        "Sieben",
+        "Acht",
+        "Neun",
+        "Zehn",
+        "Elf",
+        "Zwölf",
+        "Dreizehn",
+        "Vierzehn",
+        "Fünfzehn",
+        "Sechzehn",
+        "Siebzehn",
+        "Achtzehn",
+        "Neunzehn",
+        "Zwanzig",
+    ];
+    match language() {
+        Language::English => ENGLISH.get(number).copied(),
+        Language::German => GERMAN.get(number).copied(),
+    }
+}
+
+fn english(message: Message) -> &'static str {
+    match message {
+        Message::Help => {
+            r"Usage: wuerfel.exe [OPTIONS/ARGS]...
+  -h, --help          display this help and exit
+  -c, --text          use raw text output
+  -t, --tui           use tui output
+  -g, --gui           use gui output
+  -3, --3d            use 3d output
+  -d, --die NAME      preselect a die by name, prefix or alias (e.g. d20, twenty, d%)
+  -a, --advantage     roll single dice twice and keep the higher value
+  -D, --disadvantage  roll single dice twice and keep the lower value
+  -l, --lang LANG     language of the user interface (en, de), defaults to $LANG
+  -r, --tick-rate MS  milliseconds between tui updates, defaults to 60
+  -C, --config FILE   config file, defaults to ~/.config/wuerfel/config.toml"
+        }
+        Message::MissingDieName => "Missing die name after --die",
+        Message::UnknownLanguage => "Unknown language: {}. Supported languages are en and de.",
+        Message::NoDiceConfigured => "No dices configured!",
+        Message::AllConfiguredDice => "Outputting all currently configured dices.",
+        Message::DieName => "Name: {}",
+        Message::SymbolDescription => "Symbol: {}, Number: {}",
+        Message::LookupFound => "Found die: {}",
+        Message::LookupAmbiguous => "Your input matches several dice: {}",
+        Message::LookupSuggestion => {
+            "Your input matched with none of the existing dice. Did you mean {}?"
+        }
+        Message::LookupNotFound => "Your input matched with none of the existing dice.",
+        Message::None => "None",
+        Message::TryAgain => "Please try again!",
+        Message::RollAgain => "Do you want to roll again?(y/n)",
+        Message::InvalidYesNo => "Please answer with y or n!",
+        Message::YouSelected => "You selected: {}",
+        Message::ThrowingDie => "Throwing the die!",
+        Message::YouRolled => "You rolled a: {}",
+        Message::AvailableDice => "Currently available dice: {}",
+        Message::AppTitle => "wuerfel App",
+        Message::Previous => "Previous",
+        Message::Next => "Next",
+        Message::RollDie => "Roll die",
+        Message::Search => "Search",
+        Message::Select => "Select",
+        Message::Cancel => "Cancel",
+        Message::Quit => "Quit",
+        Message::Dice => "Dice",
+        Message::SelectedDie => "Currently selected die",
+        Message::CurrentRoll => "Current roll",
+        Message::NoDieSelected => "No die selected",
+        Message::Die => "Die: {}",
+        Message::DidYouMean => "Did you mean {}?",
+        Message::JumpHint => "Press space to jump the wuerfel",
+        Message::InvalidCount => "Invalid number of dice in '{}'",
+        Message::MissingDieInTerm => "Missing die name in '{}'",
+        Message::EmptyHand => "Please name at least one die.",
+        Message::EnterHand => {
+            "Please enter the dice you want to use, e.g. D20, 2xD6 + D10, D20 + 5 vs DC 15 or D20 adv."
+        }
+        Message::EnterRollOptions => {
+            "Enter a modifier or target like + 5 vs DC 15, or just press Enter to roll."
+        }
+        Message::UnexpectedDice => "The die is already chosen, '{}' can not be added.",
+        Message::ModeNeedsSingleDie => "Advantage and disadvantage only apply to a single die.",
+        Message::Hand => "Hand",
+        Message::Total => "Total: {}",
+        Message::AddToHand => "Add to hand",
+        Message::RemoveFromHand => "Remove from hand",
+        Message::ClearHand => "Clear hand",
+        Message::RollHand => "Roll hand",
+        Message::HandRoll => "Hand roll",
+        Message::InvalidTarget => "Invalid target number: '{}'",
+        Message::ModifierTooLarge => "The modifier is too large: '{}'",
+        Message::NegativeDie => "Dice can not be subtracted: '{}'",
+        Message::Success => "success by {}",
+        Message::Failure => "failure by {}",
+        Message::CriticalMax => "natural maximum {}!",
+        Message::CriticalMin => "natural minimum {}!",
+        Message::Modifier => "Modifier",
+        Message::Target => "Target",
+        Message::RollMode => "Roll mode",
+        Message::Normal => "normal",
+        Message::Advantage => "advantage",
+        Message::Disadvantage => "disadvantage",
+        Message::History => "History",
+        Message::Statistics => "Statistics",
+        Message::NoRolls => "No rolls yet",
+        Message::DieHeader => "Die",
+        Message::Count => "Rolls",
+        Message::Mean => "Mean",
+        Message::Min => "Min",
+        Message::Max => "Max",
+        Message::Scroll => "Scroll",
+        Message::CopyEntry => "Copy",
+        Message::ClearHistory => "Clear",
+        Message::Copied => "Copied: {}",
+        Message::CopyFailed => "Could not copy to the clipboard: {}",
+        Message::Histogram => "Faces of {}",
+        Message::Rolled => "rolled",
+        Message::Expected => "expected",
+        Message::Simulate => "Simulate",
+        Message::SimulationStarted => "Simulating rolls of {}…",
+        Message::SimulationFinished => "Simulated {} rolls of {}",
+        Message::InvalidTickRate => "Invalid tick rate: '{}'. Please give milliseconds above zero.",
+        Message::EmptyDieName => "A die needs a name.",
+        Message::DuplicateDieName => "There already is a die called {}.",
+        Message::EmptyFaces => "A die needs at least one face.",
+        Message::UnknownFace => "Unknown face '{}'. Faces are numbers from 0 to 20 or their names.",
+        Message::DuplicateFace => "The face {} is given twice.",
+        Message::FacesNotConsecutive => "Faces must be consecutive numbers.",
+        Message::FaceTooHigh => "The face {} is higher than {}, the highest face.",
+        Message::NewDie => "New die",
+        Message::EditDie => "Edit die",
+        Message::DuplicateDie => "Duplicate",
+        Message::DeleteDie => "Delete",
+        Message::Name => "Name",
+        Message::Faces => "Faces",
+        Message::FacesHint => "e.g. 1-6, 0, 1, 2 or one two three",
+        Message::Value => "Value",
+        Message::EmptyFaceName => "The face with the value {} needs a name.",
+        Message::Save => "Save",
+        Message::NextField => "Next field",
+        Message::DieSaved => "Saved {}",
+        Message::DieDeleted => "Deleted {}",
+        Message::MissingConfigPath => "Missing file name after --config",
+        Message::ConfigError => "Could not read config file {}: {}",
+        Message::UnknownAction => "Unknown action '{}' in [tui.keys].",
+        Message::UnknownKey => "Unknown key '{}' in [tui.keys].",
+        Message::UnknownPreset => "Unknown preset '{}' in [{}].",
+        Message::UnknownColor => "Unknown color '{}' for {} in [tui.theme].",
+        Message::UnknownBorder => "Unknown border '{}' in [tui.theme].",
+        Message::RunningMean => "Running mean",
+        Message::ExpectedMean => "Expected mean: {}",
+        Message::ChiSquare => "χ² = {} (limit {} with {} degrees of freedom)",
+        Message::LooksFair => "Consistent with a fair die",
+        Message::LooksUnfair => "Unlikely for a fair die (p < 0.05)",
+        Message::NotEnoughRolls => "Fairness test after {} rolls",
+        Message::InvalidDie => "{}: {}",
+        Message::ManageDice => "Manage dice",
+        Message::MoveUp => "Move up",
+        Message::MoveDown => "Move down",
+        Message::Color => "Color",
+        Message::Import => "Import…",
+        Message::Export => "Export…",
+        Message::DiceImported => "Imported {} dice from {}",
+        Message::DiceExported => "Exported the dice to {}",
+        Message::ImportFailed => "Could not import {}: {}",
+        Message::ExportFailed => "Could not export to {}: {}",
+        Message::Theme => "Theme",
+        Message::ResetToDefaults => "Reset to defaults",
+        Message::RolledOn => "Rolled {} on {}",
+        Message::FontSize => "Font size",
+        Message::SmallerText => "Smaller text",
+        Message::LargerText => "Larger text",
+        Message::HighContrast => "High contrast",
+        Message::Shortcuts => "Shortcuts",
+        Message::ShortcutsHelp => "←/→ or 1–9: choose a die\nEnter: roll it\na/d: add it to or remove it from the hand\nr: roll the hand, x: clear it\n+/-: change the modifier\nt: toggle the target, </>: change it\nm: next roll mode\nq or Esc: quit",
+        Message::Rolling => "Rolling…",
+        Message::InvalidThrowStrength => "Invalid throw strength '{}' in [3d], expected a number above 0.",
+        Message::NoShape => "There is no 3d shape for {} with {} faces, only for 4, 6, 8, 10, 12 and 20 faces.",
+        Message::InvalidHand => "Invalid hand '{}' in [3d], expected a roll like \"2d6 + d20\".",
+        Message::TooManyDice => "At most {} dice can be thrown at once.",
+    }
+}
+
+fn german(message: Message) -> &'static str {
+    match message {
+        Message::Help => {
+            r"Aufruf: wuerfel.exe [OPTIONEN/ARGUMENTE]...
+  -h, --help          diese Hilfe anzeigen und beenden
+  -c, --text          reine Textausgabe verwenden
+  -t, --tui           Terminal-Oberfläche verwenden
+  -g, --gui           grafische Oberfläche verwenden
+  -3, --3d            3D-Simulation verwenden
+  -d, --die NAME      Würfel über Name, Präfix oder Alias vorauswählen (z.B. d20, zwanzig, d%)
+  -a, --advantage     einzelne Würfel zweimal werfen und den höheren Wert behalten
+  -D, --disadvantage  einzelne Würfel zweimal werfen und den niedrigeren Wert behalten
+  -l, --lang LANG     Sprache der Oberfläche (en, de), Standard ist $LANG
+  -r, --tick-rate MS  Millisekunden zwischen Aktualisierungen der tui, Standard ist 60
+  -C, --config FILE   Konfigurationsdatei, Standard ist ~/.config/wuerfel/config.toml"
+        }
+        Message::MissingDieName => "Nach --die fehlt der Name des Würfels",
+        Message::UnknownLanguage => "Unbekannte Sprache: {}. Unterstützt werden en und de.",
+        Message::NoDiceConfigured => "Keine Würfel konfiguriert!",
+        Message::AllConfiguredDice => "Alle aktuell konfigurierten Würfel:",
+        Message::DieName => "Name: {}",
+        Message::SymbolDescription => "Symbol: {}, Zahl: {}",
+        Message::LookupFound => "Würfel gefunden: {}",
+        Message::LookupAmbiguous => "Deine Eingabe passt zu mehreren Würfeln: {}",
+        Message::LookupSuggestion => {
+            "Deine Eingabe passt zu keinem der vorhandenen Würfel. Meintest du {}?"
+        }
+        Message::LookupNotFound => "Deine Eingabe passt zu keinem der vorhandenen Würfel.",
+        Message::None => "Keine",
+        Message::TryAgain => "Bitte versuche es erneut!",
+        Message::RollAgain => "Möchtest du nochmal würfeln?(j/n)",
+        Message::InvalidYesNo => "Bitte antworte mit j oder n!",
+        Message::YouSelected => "Deine Auswahl: {}",
+        Message::ThrowingDie => "Der Würfel wird geworfen!",
+        Message::YouRolled => "Du hast gewürfelt: {}",
+        Message::AvailableDice => "Aktuell verfügbare Würfel: {}",
+        Message::AppTitle => "wuerfel App",
+        Message::Previous => "Vorheriger",
+        Message::Next => "Nächster",
+        Message::RollDie => "Würfeln",
+        Message::Search => "Suchen",
+        Message::Select => "Auswählen",
+        Message::Cancel => "Abbrechen",
+        Message::Quit => "Beenden",
+        Message::Dice => "Würfel",
+        Message::SelectedDie => "Ausgewählter Würfel",
+        Message::CurrentRoll => "Aktueller Wurf",
+        Message::NoDieSelected => "Kein Würfel ausgewählt",
+        Message::Die => "Würfel: {}",
+        Message::DidYouMean => "Meintest du {}?",
+        Message::JumpHint => "Leertaste drücken, um den Würfel zu werfen",
+        Message::InvalidCount => "Ungültige Anzahl an Würfeln in '{}'",
+        Message::MissingDieInTerm => "In '{}' fehlt der Name des Würfels",
+        Message::EmptyHand => "Bitte gib mindestens einen Würfel an.",
+        Message::EnterHand => {
+            "Bitte gib die Würfel ein, die du verwenden willst, z.B. D20, 2xD6 + D10, D20 + 5 vs DC 15 oder D20 Vorteil."
+        }
+        Message::EnterRollOptions => {
+            "Gib einen Modifikator oder Zielwert wie + 5 vs DC 15 ein oder drücke einfach Enter zum Würfeln."
+        }
+        Message::UnexpectedDice => "Der Würfel ist schon gewählt, '{}' kann nicht hinzugefügt werden.",
+        Message::ModeNeedsSingleDie => "Vorteil und Nachteil gelten nur für einen einzelnen Würfel.",
+        Message::Hand => "Hand",
+        Message::Total => "Summe: {}",
+        Message::AddToHand => "Zur Hand hinzufügen",
+        Message::RemoveFromHand => "Aus der Hand entfernen",
+        Message::ClearHand => "Hand leeren",
+        Message::RollHand => "Hand würfeln",
+        Message::HandRoll => "Wurf der Hand",
+        Message::InvalidTarget => "Ungültiger Zielwert: '{}'",
+        Message::ModifierTooLarge => "Der Modifikator ist zu groß: '{}'",
+        Message::NegativeDie => "Würfel können nicht abgezogen werden: '{}'",
+        Message::Success => "Erfolg um {}",
+        Message::Failure => "Fehlschlag um {}",
+        Message::CriticalMax => "natürliches Maximum {}!",
+        Message::CriticalMin => "natürliches Minimum {}!",
+        Message::Modifier => "Modifikator",
+        Message::Target => "Zielwert",
+        Message::RollMode => "Wurfmodus",
+        Message::Normal => "normal",
+        Message::Advantage => "Vorteil",
+        Message::Disadvantage => "Nachteil",
+        Message::History => "Verlauf",
+        Message::Statistics => "Statistik",
+        Message::NoRolls => "Noch keine Würfe",
+        Message::DieHeader => "Würfel",
+        Message::Count => "Würfe",
+        Message::Mean => "Mittelwert",
+        Message::Min => "Min",
+        Message::Max => "Max",
+        Message::Scroll => "Blättern",
+        Message::CopyEntry => "Kopieren",
+        Message::ClearHistory => "Leeren",
+        Message::Copied => "Kopiert: {}",
+        Message::CopyFailed => "Kopieren in die Zwischenablage fehlgeschlagen: {}",
+        Message::Histogram => "Augen von {}",
+        Message::Rolled => "gewürfelt",
+        Message::Expected => "erwartet",
+        Message::Simulate => "Simulieren",
+        Message::SimulationStarted => "Simuliere Würfe mit {}…",
+        Message::SimulationFinished => "{} Würfe mit {} simuliert",
+        Message::InvalidTickRate => {
+            "Ungültige Tick-Rate: '{}'. Bitte Millisekunden größer als null angeben."
+        }
+        Message::EmptyDieName => "Ein Würfel braucht einen Namen.",
+        Message::DuplicateDieName => "Es gibt schon einen Würfel namens {}.",
+        Message::EmptyFaces => "Ein Würfel braucht mindestens eine Seite.",
+        Message::UnknownFace => "Unbekannte Seite '{}'. Seiten sind Zahlen von 0 bis 20 oder deren Namen.",
+        Message::DuplicateFace => "Die Seite {} ist doppelt angegeben.",
+        Message::FacesNotConsecutive => "Die Seiten müssen aufeinanderfolgende Zahlen sein.",
+        Message::FaceTooHigh => "Die Seite {} ist höher als {}, die höchste Seite.",
+        Message::NewDie => "Neuer Würfel",
+        Message::EditDie => "Würfel bearbeiten",
+        Message::DuplicateDie => "Duplizieren",
+        Message::DeleteDie => "Löschen",
+        Message::Name => "Name",
+        Message::Faces => "Seiten",
+        Message::FacesHint => "z.B. 1-6, 0, 1, 2 oder eins zwei drei",
+        Message::Value => "Wert",
+        Message::EmptyFaceName => "Die Seite mit dem Wert {} braucht einen Namen.",
+        Message::Save => "Speichern",
+        Message::NextField => "Nächstes Feld",
+        Message::DieSaved => "{} gespeichert",
+        Message::DieDeleted => "{} gelöscht",
+        Message::MissingConfigPath => "Nach --config fehlt der Dateiname",
+        Message::ConfigError => "Konfigurationsdatei {} konnte nicht gelesen werden: {}",
+        Message::UnknownAction => "Unbekannte Aktion '{}' in [tui.keys].",
+        Message::UnknownKey => "Unbekannte Taste '{}' in [tui.keys].",
+        Message::UnknownPreset => "Unbekannte Vorlage '{}' in [{}].",
+        Message::UnknownColor => "Unbekannte Farbe '{}' für {} in [tui.theme].",
+        Message::UnknownBorder => "Unbekannter Rahmen '{}' in [tui.theme].",
+        Message::RunningMean => "Laufender Mittelwert",
+        Message::ExpectedMean => "Erwarteter Mittelwert: {}",
+        Message::ChiSquare => "χ² = {} (Grenze {} bei {} Freiheitsgraden)",
+        Message::LooksFair => "Passt zu einem fairen Würfel",
+        Message::LooksUnfair => "Unwahrscheinlich für einen fairen Würfel (p < 0,05)",
+        Message::NotEnoughRolls => "Fairnesstest ab {} Würfen",
+        Message::InvalidDie => "{}: {}",
+        Message::ManageDice => "Würfel verwalten",
+        Message::MoveUp => "Nach oben",
+        Message::MoveDown => "Nach unten",
+        Message::Color => "Farbe",
+        Message::Import => "Importieren…",
+        Message::Export => "Exportieren…",
+        Message::DiceImported => "{} Würfel aus {} importiert",
+        Message::DiceExported => "Würfel nach {} exportiert",
+        Message::ImportFailed => "{} konnte nicht importiert werden: {}",
+        Message::ExportFailed => "Export nach {} fehlgeschlagen: {}",
+        Message::Theme => "Farbschema",
+        Message::ResetToDefaults => "Auf Standard zurücksetzen",
+        Message::RolledOn => "{} gewürfelt mit {}",
+        Message::FontSize => "Schriftgröße",
+        Message::SmallerText => "Kleinere Schrift",
+        Message::LargerText => "Größere Schrift",
+        Message::HighContrast => "Hoher Kontrast",
+        Message::Shortcuts => "Tastenkürzel",
+        Message::ShortcutsHelp => "←/→ oder 1–9: Würfel wählen\nEnter: würfeln\na/d: zur Hand hinzufügen oder entfernen\nr: Hand würfeln, x: Hand leeren\n+/-: Modifikator ändern\nt: Zielwert ein/aus, </>: Zielwert ändern\nm: nächster Würfelmodus\nq oder Esc: beenden",
+        Message::Rolling => "Würfel rollt…",
+        Message::InvalidThrowStrength => "Ungültige Wurfstärke '{}' in [3d], erwartet wird eine Zahl über 0.",
+        Message::NoShape => "Für {} mit {} Seiten gibt es keine 3D-Form, nur für 4, 6, 8, 10, 12 und 20 Seiten.",
+        Message::InvalidHand => "Ungültige Hand '{}' in [3d], erwartet wird ein Wurf wie \"2d6 + d20\".",
+        Message::TooManyDice => "Es können höchstens {} Würfel auf einmal geworfen werden.",
+    }
+}
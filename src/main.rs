@@ -1,16 +1,20 @@
-use core::{Die, Error, State, Symbol};
+use core::{Die, DieLookup, Error, State, Symbol};
 
 use getrandom::getrandom;
 use oorandom::{self, Rand32};
 
 mod cli;
+mod config;
 mod core;
 mod gui;
+mod history;
+mod locale;
+mod roll;
 mod text;
 mod three_dimensional;
 mod tui;
 
-fn configure_die(die: &mut Die<'_>, range: std::ops::Range<usize>) {
+fn configure_die(die: &mut Die, range: std::ops::Range<usize>) {
     for i in range {
         let j = i - 1;
         let symbol = Symbol::COLLECTION
@@ -30,6 +34,8 @@ fn setup_default_dice(state: &mut State) {
     state.add_die(d6);
     let mut d10 = Die::new("D10".to_string());
     configure_die(&mut d10, 1..11);
+    d10.add_alias("d%".to_string());
+    d10.add_alias("percentile".to_string());
     state.add_die(d10);
     let mut d20 = Die::new("D20".to_string());
     configure_die(&mut d20, 1..21);
@@ -44,20 +50,72 @@ fn setup_random() -> Rand32 {
 
 fn main() -> Result<(), Error> {
     let cli_options = cli::get_cli_options();
+    locale::set_language(
+        cli_options
+            .language
+            .unwrap_or_else(locale::Language::detect),
+    );
+    if let Some(error) = &cli_options.error {
+        eprintln!("{}", error);
+        return Ok(());
+    }
+    let config = match config::load(cli_options.config.as_deref()) {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("{}", message);
+            return Ok(());
+        }
+    };
     let mut state = State::default();
     setup_default_dice(&mut state);
     let mut random = setup_random();
 
-    match cli_options {
-        cli::CliOptions::Exit => {
+    let selected_die = match cli_options
+        .die
+        .as_deref()
+        .map(|query| state.find_die(query))
+    {
+        Some(DieLookup::Found(index)) => Some(index),
+        Some(lookup) => {
+            eprintln!("{}", state.describe_lookup(&lookup));
             return Ok(());
         }
-        cli::CliOptions::Text => {
-            text::run_text_mode(&mut state, &mut random);
-            return Ok(());
+        None => None,
+    };
+
+    match cli_options.mode {
+        cli::CliMode::Exit => Ok(()),
+        cli::CliMode::Help => {
+            cli::print_help();
+            Ok(())
+        }
+        cli::CliMode::Text => {
+            text::run_text_mode(&mut state, selected_die, cli_options.roll_mode, &mut random);
+            Ok(())
         }
-        cli::CliOptions::TUI => tui::run_tui(state, random),
-        cli::CliOptions::GUI => gui::run_gui(state, random),
-        cli::CliOptions::ThreeDimensional => three_dimensional::run_three_dimensional(random),
+        cli::CliMode::Tui => match tui::Settings::from_config(&config) {
+            Ok(settings) => tui::run_tui(
+                state,
+                selected_die,
+                cli_options.roll_mode,
+                cli_options.tick_rate.unwrap_or(tui::DEFAULT_TICK_RATE),
+                settings,
+                random,
+            ),
+            Err(message) => {
+                eprintln!("{}", message);
+                Ok(())
+            }
+        },
+        cli::CliMode::Gui => gui::run_gui(state, selected_die, cli_options.roll_mode, random),
+        cli::CliMode::ThreeDimensional => match three_dimensional::Settings::from_config(&config) {
+            Ok(settings) => {
+                three_dimensional::run_three_dimensional(state, selected_die, random, settings)
+            }
+            Err(message) => {
+                eprintln!("{}", message);
+                Ok(())
+            }
+        },
     }
 }
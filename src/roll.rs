@@ -0,0 +1,357 @@
+use std::{fmt::Display, ops::Range};
+
+use serde::{Deserialize, Serialize};
+
+use crate::locale::{tr, trf, Message};
+
+/// Target number used when the target is switched on.
+pub(crate) const DEFAULT_TARGET: i32 = 10;
+
+/// Flat modifier and optional target number applied to a roll, as in `d20 + 5 vs DC 15`.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct RollOptions {
+    pub(crate) modifier: i32,
+    pub(crate) target: Option<i32>,
+    pub(crate) mode: RollMode,
+}
+
+/// Whether a single die is rolled once or twice keeping the higher or lower value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum RollMode {
+    #[default]
+    Normal,
+    Advantage,
+    Disadvantage,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Critical {
+    Max,
+    Min,
+}
+
+/// How a result is highlighted: whether it met the target, or without a target
+/// whether it was a critical.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Outcome {
+    Success,
+    Failure,
+    Neutral,
+}
+
+/// A natural roll with the modifier applied and compared against the target.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct RollResult {
+    natural: u32,
+    /// Both faces of a roll with advantage or disadvantage.
+    pair: Option<(u32, u32)>,
+    options: RollOptions,
+    critical: Option<Critical>,
+}
+
+impl RollMode {
+    pub(crate) const ALL: [RollMode; 3] = [
+        RollMode::Normal,
+        RollMode::Advantage,
+        RollMode::Disadvantage,
+    ];
+
+    pub(crate) fn next(self) -> RollMode {
+        match self {
+            RollMode::Normal => RollMode::Advantage,
+            RollMode::Advantage => RollMode::Disadvantage,
+            RollMode::Disadvantage => RollMode::Normal,
+        }
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            RollMode::Normal => tr(Message::Normal),
+            RollMode::Advantage => tr(Message::Advantage),
+            RollMode::Disadvantage => tr(Message::Disadvantage),
+        }
+    }
+
+    fn parse(word: &str) -> Option<RollMode> {
+        match word {
+            "adv" | "advantage" | "vorteil" => Some(RollMode::Advantage),
+            "dis" | "disadvantage" | "nachteil" => Some(RollMode::Disadvantage),
+            _ => None,
+        }
+    }
+}
+
+impl RollOptions {
+    /// Evaluates a roll of two faces, keeping one of them according to the roll mode.
+    pub(crate) fn evaluate_pair(&self, first: u32, second: u32, range: Range<u32>) -> RollResult {
+        let natural = match self.mode {
+            RollMode::Normal => first,
+            RollMode::Advantage => first.max(second),
+            RollMode::Disadvantage => first.min(second),
+        };
+        RollResult {
+            pair: (self.mode != RollMode::Normal).then_some((first, second)),
+            ..self.evaluate(natural, Some(range))
+        }
+    }
+
+    /// Evaluates `natural`. Criticals are only detected when the face `range` of a
+    /// single die is known.
+    pub(crate) fn evaluate(&self, natural: u32, range: Option<Range<u32>>) -> RollResult {
+        let critical = range.and_then(|range| {
+            if natural + 1 == range.end {
+                Some(Critical::Max)
+            } else if natural == range.start {
+                Some(Critical::Min)
+            } else {
+                None
+            }
+        });
+        RollResult {
+            natural,
+            pair: None,
+            options: *self,
+            critical,
+        }
+    }
+
+    /// Parses the modifier, target and roll mode of an expression like `+ 5 - 1 adv vs DC 15`.
+    ///
+    /// Returns the remaining dice terms, or an error message if a number is malformed.
+    pub(crate) fn parse(input: &str) -> Result<(RollOptions, Vec<String>), String> {
+        let mut options = RollOptions::default();
+        let lowered = input.to_lowercase();
+        let all_words = lowered.split_whitespace().collect::<Vec<_>>();
+        // Only a word of its own starts the target, so dice named like `Advs` stay dice.
+        let (expression, target) = match all_words.iter().position(|word| starts_target(word)) {
+            Some(position) => (
+                &all_words[..position],
+                Some(all_words[position..].join(" ")),
+            ),
+            None => (all_words.as_slice(), None),
+        };
+        if let Some(target) = target {
+            let number = target
+                .trim_start_matches("vs")
+                .trim()
+                .trim_start_matches("dc")
+                .trim();
+            let number = number
+                .parse::<i32>()
+                .map_err(|_| trf(Message::InvalidTarget, &[&number]))?;
+            options.target = Some(number);
+        }
+
+        let mut words = vec![];
+        for word in expression.iter().copied() {
+            match RollMode::parse(word) {
+                Some(mode) => options.mode = mode,
+                None => words.push(word),
+            }
+        }
+        let expression = words.join(" ");
+
+        let mut dice = vec![];
+        let mut sign = 1;
+        let mut term = String::new();
+        for c in expression.chars().chain(std::iter::once('+')) {
+            if !matches!(c, '+' | '-' | ',') {
+                term.push(c);
+                continue;
+            }
+            let trimmed = term.trim();
+            if !trimmed.is_empty() {
+                if let Ok(number) = trimmed.parse::<i32>() {
+                    options.modifier = options
+                        .modifier
+                        .checked_add(sign * number)
+                        .ok_or_else(|| trf(Message::ModifierTooLarge, &[&trimmed]))?;
+                } else if sign < 0 {
+                    return Err(trf(Message::NegativeDie, &[&trimmed]));
+                } else {
+                    dice.push(trimmed.to_string());
+                }
+            }
+            term.clear();
+            sign = if c == '-' { -1 } else { 1 };
+        }
+        Ok((options, dice))
+    }
+}
+
+/// Whether `word` starts a target like `vs 15`, `DC 15` or `dc15`.
+fn starts_target(word: &str) -> bool {
+    word.strip_prefix("vs")
+        .or_else(|| word.strip_prefix("dc"))
+        .is_some_and(|rest| rest.is_empty() || rest.parse::<i32>().is_ok())
+}
+
+impl RollResult {
+    /// The kept face before the modifier is applied.
+    pub(crate) fn natural(&self) -> u32 {
+        self.natural
+    }
+
+    /// Every face that came up, both of a roll with advantage or disadvantage.
+    pub(crate) fn faces(&self) -> Vec<u32> {
+        match self.pair {
+            Some((first, second)) => vec![first, second],
+            None => vec![self.natural],
+        }
+    }
+
+    pub(crate) fn total(&self) -> i32 {
+        (self.natural as i32).saturating_add(self.options.modifier)
+    }
+
+    pub(crate) fn outcome(&self) -> Outcome {
+        match (self.verdict(), self.critical) {
+            (Some((true, _)), _) | (None, Some(Critical::Max)) => Outcome::Success,
+            (Some((false, _)), _) | (None, Some(Critical::Min)) => Outcome::Failure,
+            (None, None) => Outcome::Neutral,
+        }
+    }
+
+    /// Whether the target was met and by how much it was beaten or missed.
+    pub(crate) fn verdict(&self) -> Option<(bool, i32)> {
+        self.options.target.map(|target| {
+            let margin = self.total().saturating_sub(target);
+            (margin >= 0, margin.saturating_abs())
+        })
+    }
+}
+
+impl Display for RollResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some((first, second)) = self.pair {
+            write!(f, "{} {}/{} → ", self.options.mode.label(), first, second)?;
+        }
+        write!(f, "{}", self.natural)?;
+        match self.options.modifier {
+            0 => {}
+            modifier if modifier > 0 => write!(f, " + {} = {}", modifier, self.total())?,
+            modifier => write!(f, " - {} = {}", modifier.unsigned_abs(), self.total())?,
+        }
+        if let (Some(target), Some((success, margin))) = (self.options.target, self.verdict()) {
+            let verdict = if success {
+                Message::Success
+            } else {
+                Message::Failure
+            };
+            write!(f, " vs {}: {}", target, trf(verdict, &[&margin]))?;
+        }
+        match self.critical {
+            Some(Critical::Max) => write!(f, " ({})", trf(Message::CriticalMax, &[&self.natural]))?,
+            Some(Critical::Min) => write!(f, " ({})", trf(Message::CriticalMin, &[&self.natural]))?,
+            None => {}
+        }
+        std::fmt::Result::Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_splits_modifier_target_and_mode() {
+        let (options, dice) = RollOptions::parse("2xD6 + d10 + 5 - 1 adv vs DC 15").unwrap();
+        assert_eq!(dice, ["2xd6", "d10"]);
+        assert_eq!(options.modifier, 4);
+        assert_eq!(options.target, Some(15));
+        assert_eq!(options.mode, RollMode::Advantage);
+        let (options, dice) = RollOptions::parse("d20, 3 d4 dis dc 12").unwrap();
+        assert_eq!(dice, ["d20", "3 d4"]);
+        assert_eq!(options.target, Some(12));
+        assert_eq!(options.mode, RollMode::Disadvantage);
+    }
+
+    #[test]
+    fn target_words_stand_alone() {
+        let (options, dice) = RollOptions::parse("2 Advs + dcx vs 12").unwrap();
+        assert_eq!(dice, ["2 advs", "dcx"]);
+        assert_eq!(options.target, Some(12));
+        let (options, dice) = RollOptions::parse("d20 dc15").unwrap();
+        assert_eq!(dice, ["d20"]);
+        assert_eq!(options.target, Some(15));
+        let (options, _) = RollOptions::parse("vsd6 + 1").unwrap();
+        assert_eq!(options.target, None);
+    }
+
+    #[test]
+    fn parse_rejects_malformed_input() {
+        assert!(RollOptions::parse("d20 vs DC x").is_err());
+        assert!(RollOptions::parse("d20 - d4").is_err());
+        assert!(RollOptions::parse("d20 + 2000000000 + 2000000000").is_err());
+        assert!(RollOptions::parse("d20 - 2000000000 - 2000000000").is_err());
+    }
+
+    #[test]
+    fn evaluate_applies_the_modifier() {
+        let options = RollOptions {
+            modifier: -2,
+            ..RollOptions::default()
+        };
+        let result = options.evaluate(7, None);
+        assert_eq!(result.natural(), 7);
+        assert_eq!(result.total(), 5);
+        assert_eq!(result.verdict(), None);
+        assert_eq!(result.to_string(), "7 - 2 = 5");
+    }
+
+    #[test]
+    fn verdict_compares_total_and_target() {
+        let options = RollOptions {
+            modifier: 5,
+            target: Some(15),
+            ..RollOptions::default()
+        };
+        assert_eq!(options.evaluate(10, None).verdict(), Some((true, 0)));
+        assert_eq!(options.evaluate(13, None).verdict(), Some((true, 3)));
+        assert_eq!(options.evaluate(6, None).verdict(), Some((false, 4)));
+    }
+
+    #[test]
+    fn outcome_prefers_the_target_over_criticals() {
+        let options = RollOptions {
+            target: Some(15),
+            ..RollOptions::default()
+        };
+        assert_eq!(options.evaluate(1, Some(1..21)).outcome(), Outcome::Failure);
+        assert_eq!(
+            options.evaluate(20, Some(1..21)).outcome(),
+            Outcome::Success
+        );
+        let options = RollOptions::default();
+        assert_eq!(
+            options.evaluate(20, Some(1..21)).outcome(),
+            Outcome::Success
+        );
+        assert_eq!(options.evaluate(1, Some(1..21)).outcome(), Outcome::Failure);
+        assert_eq!(options.evaluate(7, Some(1..21)).outcome(), Outcome::Neutral);
+    }
+
+    #[test]
+    fn criticals_need_the_face_range() {
+        let options = RollOptions::default();
+        assert!(options.evaluate(20, Some(1..21)).critical == Some(Critical::Max));
+        assert!(options.evaluate(1, Some(1..21)).critical == Some(Critical::Min));
+        assert!(options.evaluate(10, Some(1..21)).critical.is_none());
+        assert!(options.evaluate(20, None).critical.is_none());
+    }
+
+    #[test]
+    fn evaluate_pair_keeps_the_higher_or_lower_face() {
+        let mut options = RollOptions {
+            mode: RollMode::Advantage,
+            ..RollOptions::default()
+        };
+        assert_eq!(options.evaluate_pair(4, 17, 1..21).natural(), 17);
+        options.mode = RollMode::Disadvantage;
+        let result = options.evaluate_pair(4, 17, 1..21);
+        assert_eq!(result.natural(), 4);
+        options.mode = RollMode::Normal;
+        assert_eq!(options.evaluate_pair(4, 17, 1..21).natural(), 4);
+        assert!(options.evaluate_pair(1, 20, 1..21).critical == Some(Critical::Min));
+    }
+}
@@ -1,10 +1,19 @@
 use std::io::BufRead;
 
-use crate::{Die, State};
+use crate::{
+    locale::{tr, trf, Message},
+    roll::{RollMode, RollOptions},
+    State,
+};
 
-fn get_chosen_die<'a>(state: &'a mut State<'a>) -> &'a Die<'a> {
-    //After showing the dice. Ask user to select a die by inputting name of the die
-    println!("Please enter the name of the die you want to use.");
+fn choose_roll(state: &mut State, selected_die: Option<usize>, roll_mode: RollMode) -> RollOptions {
+    state.clear_hand();
+    if let Some(index) = selected_die {
+        state.add_to_hand(index);
+        return choose_options(roll_mode);
+    }
+    //After showing the dice. Ask user to select the dice by inputting e.g. 2xD6 + D10
+    println!("{}", tr(Message::EnterHand));
     let mut user_input = String::new();
     let stdin = std::io::stdin();
     let mut handle = stdin.lock();
@@ -13,15 +22,49 @@ fn get_chosen_die<'a>(state: &'a mut State<'a>) -> &'a Die<'a> {
         .read_line(&mut user_input)
         .expect("Failed reading from stdin!");
     loop {
-        let trimmed = user_input.trim();
-        for die in state.get_dice().iter() {
-            let name = die.get_name();
-            if name == trimmed {
-                println!("Found die: {}", name);
-                return die;
+        match state.parse_roll(&user_input) {
+            Ok((hand, mut options)) => {
+                let single_die = hand.single_die().is_some();
+                state.set_hand(hand);
+                if roll_mode != RollMode::Normal && !single_die {
+                    println!("{}", tr(Message::ModeNeedsSingleDie));
+                } else if options.mode == RollMode::Normal {
+                    options.mode = roll_mode;
+                }
+                return options;
+            }
+            Err(message) => println!("{}", message),
+        }
+        println!("{}", tr(Message::TryAgain));
+        user_input.clear();
+        handle
+            .read_line(&mut user_input)
+            .expect("Failed reading from stdin!");
+    }
+}
+
+/// Asks for a modifier and target to roll a die chosen on the command line with.
+fn choose_options(roll_mode: RollMode) -> RollOptions {
+    println!("{}", tr(Message::EnterRollOptions));
+    let mut user_input = String::new();
+    let stdin = std::io::stdin();
+    let mut handle = stdin.lock();
+
+    handle
+        .read_line(&mut user_input)
+        .expect("Failed reading from stdin!");
+    loop {
+        match RollOptions::parse(&user_input) {
+            Ok((mut options, dice)) if dice.is_empty() => {
+                if options.mode == RollMode::Normal {
+                    options.mode = roll_mode;
+                }
+                return options;
             }
+            Ok((_, dice)) => println!("{}", trf(Message::UnexpectedDice, &[&dice.join(", ")])),
+            Err(message) => println!("{}", message),
         }
-        println!("Your input matched with none of the existing dice. Please try again!");
+        println!("{}", tr(Message::TryAgain));
         user_input.clear();
         handle
             .read_line(&mut user_input)
@@ -30,7 +73,7 @@ fn get_chosen_die<'a>(state: &'a mut State<'a>) -> &'a Die<'a> {
 }
 
 fn roll_again() -> bool {
-    println!("Do you want to roll again?(y/n)");
+    println!("{}", tr(Message::RollAgain));
 
     let mut user_input = String::new();
     let stdin = std::io::stdin();
@@ -42,12 +85,12 @@ fn roll_again() -> bool {
     loop {
         let trimmed = user_input.trim();
 
-        if trimmed == "y" || trimmed == "Y" {
+        if trimmed.eq_ignore_ascii_case("y") || trimmed.eq_ignore_ascii_case("j") {
             return true;
         } else if trimmed == "n" || trimmed == "N" {
             return false;
         } else {
-            println!("Your input matched with none of the existing dice. Please try again!");
+            println!("{}", tr(Message::InvalidYesNo));
             user_input.clear();
             handle
                 .read_line(&mut user_input)
@@ -56,22 +99,50 @@ fn roll_again() -> bool {
     }
 }
 
-fn get_chosen_die_range<'a>(state: &'a mut State<'a>) -> std::ops::Range<u32> {
-    let chosen_die = get_chosen_die(state);
-    println!("You selected: {}", chosen_die);
-    println!("Throwing the die!");
-
-    chosen_die.get_range()
+fn print_chosen_hand(state: &State) {
+    let hand = state.get_hand();
+    let single_die = hand
+        .single_die()
+        .and_then(|index| state.get_dice().get(index));
+    match single_die {
+        Some(die) => println!("{}", trf(Message::YouSelected, &[die])),
+        None => {
+            let description = state.describe_hand(hand).unwrap_or_default();
+            println!("{}", trf(Message::YouSelected, &[&description]));
+        }
+    }
+    println!("{}", tr(Message::ThrowingDie));
 }
 
-pub fn run_text_mode<'a>(state: &'a mut State<'a>, random: &mut oorandom::Rand32) {
-    let available_dice = state.print_dice().unwrap_or(String::from("None"));
-    println!("Currently available dice: {}", available_dice);
+pub fn run_text_mode(
+    state: &mut State,
+    selected_die: Option<usize>,
+    roll_mode: RollMode,
+    random: &mut oorandom::Rand32,
+) {
+    let available_dice = state.print_dice().unwrap_or(tr(Message::None).to_string());
+    println!("{}", trf(Message::AvailableDice, &[&available_dice]));
 
-    let die_range = get_chosen_die_range(state);
+    let options = choose_roll(state, selected_die, roll_mode);
+    print_chosen_hand(state);
+    let single_die = state
+        .get_hand()
+        .single_die()
+        .and_then(|index| state.get_dice().get(index));
     loop {
-        let random_number = random.rand_range(die_range.clone());
-        println!("You rolled a: {}", random_number);
+        if let Some(die) = single_die {
+            let result = die.roll(random, &options);
+            println!("{}", trf(Message::YouRolled, &[&result]));
+        } else if let Some(roll) = state.roll_hand(state.get_hand(), random) {
+            let result = state.evaluate_hand_roll(&roll, &options);
+            println!(
+                "{}",
+                trf(Message::YouRolled, &[&state.describe_hand_roll(&roll)])
+            );
+            if options.modifier != 0 || options.target.is_some() {
+                println!("{}", trf(Message::Total, &[&result]));
+            }
+        }
         if !roll_again() {
             break;
         }
@@ -1,10 +1,112 @@
+use std::f32::consts::{FRAC_PI_4, TAU};
+
 use bevy_embedded_assets::EmbeddedAssetPlugin;
 use bevy_rapier3d::prelude::*;
 
-use crate::core::Error;
+use crate::{
+    config,
+    core::{Error, HandRoll, State},
+    locale::{tr, trf, Message},
+};
 use bevy::{color::palettes::basic::SILVER, prelude::*};
 
-pub fn run_three_dimensional(random: oorandom::Rand32) -> Result<(), Error> {
+mod shapes;
+
+use shapes::D6_FACES;
+
+/// Speed in units per second below which the wuerfel counts as still.
+const REST_SPEED: f32 = 0.05;
+/// Angular speed in radians per second below which the wuerfel counts as still.
+const REST_SPIN: f32 = 0.05;
+/// Seconds the wuerfel has to stay still before its face is read, unless the physics
+/// engine put it to sleep before.
+const REST_TIME: f32 = 0.5;
+/// Height the wuerfel is thrown from.
+const LAUNCH_HEIGHT: f32 = 3.0;
+/// Largest distance from the resting wuerfel, along either axis, it is thrown from.
+const LAUNCH_SPREAD: f32 = 1.5;
+/// Upward impulse of a throw at strength 1.
+const THROW_UP: f32 = 90.0;
+/// Largest sideways impulse of a throw at strength 1.
+const THROW_SIDEWAYS: f32 = 15.0;
+/// Largest angular impulse around each axis of a throw at strength 1.
+const THROW_SPIN: f32 = 120.0;
+/// Distance between the dice when they are spawned or thrown together, enough to keep
+/// even the largest ones apart.
+const SPACING: f32 = 4.5;
+/// Dice thrown at once, more would not fit the view.
+const MAX_DICE: usize = 24;
+/// Lowest height of the camera above the ground.
+const CAMERA_HEIGHT: f32 = 15.0;
+/// Space the camera keeps around the dice and above the highest one.
+const CAMERA_MARGIN: f32 = 10.0;
+/// How quickly the camera catches up with the dice, per second.
+const CAMERA_SPEED: f32 = 3.0;
+
+/// Throw strength and hand from the `[3d]` config section.
+#[derive(Resource)]
+pub(crate) struct Settings {
+    /// Factor of every impulse of a throw.
+    throw_strength: f32,
+    /// Dice thrown together, written like a roll, e.g. `2d6 + d20`.
+    hand: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            throw_strength: 1.0,
+            hand: None,
+        }
+    }
+}
+
+impl Settings {
+    pub(crate) fn from_config(config: &toml::Table) -> Result<Settings, String> {
+        let Some(section) = config::section(config, "3d") else {
+            return Ok(Settings::default());
+        };
+        let mut settings = Settings::default();
+        if let Some(value) = section.get("throw_strength") {
+            let strength = match value {
+                toml::Value::Float(strength) => Some(*strength),
+                toml::Value::Integer(strength) => Some(*strength as f64),
+                _ => None,
+            };
+            settings.throw_strength = match strength {
+                Some(strength) if strength > 0.0 && strength.is_finite() => strength as f32,
+                _ => {
+                    return Err(trf(
+                        Message::InvalidThrowStrength,
+                        &[&config::describe(value)],
+                    ))
+                }
+            };
+        }
+        if let Some(value) = section.get("hand") {
+            let hand = value
+                .as_str()
+                .ok_or_else(|| trf(Message::InvalidHand, &[&config::describe(value)]))?;
+            settings.hand = Some(hand.to_string());
+        }
+        Ok(settings)
+    }
+}
+
+pub fn run_three_dimensional(
+    state: State,
+    selected_die: Option<usize>,
+    random: oorandom::Rand32,
+    settings: Settings,
+) -> Result<(), Error> {
+    let mut state = state;
+    let dice = match thrown_dice(&mut state, selected_die, &settings) {
+        Ok(dice) => dice,
+        Err(message) => {
+            eprintln!("{}", message);
+            return Ok(());
+        }
+    };
     let mut app = App::new();
     app.add_plugins((
         EmbeddedAssetPlugin::default(),
@@ -24,14 +126,22 @@ pub fn run_three_dimensional(random: oorandom::Rand32) -> Result<(), Error> {
         app.add_plugins(RapierDebugRenderPlugin::default());
     }
     app.insert_resource(Random(random))
+        .insert_resource(settings)
+        .insert_resource(Thrown {
+            state,
+            dice,
+            entities: Vec::new(),
+            started: false,
+        })
         .add_systems(Startup, setup)
         .add_systems(
             Update,
             (
-                toggle_jump,
-                camera_follow_cube,
-                ground_follow_cube,
-                light_follow_cube,
+                // A throw has to reset the rest before it is read again.
+                (toggle_jump, detect_rest, show_result).chain(),
+                camera_follow_dice,
+                ground_follow_dice,
+                light_follow_dice,
             ),
         );
     app.run();
@@ -41,10 +151,128 @@ pub fn run_three_dimensional(random: oorandom::Rand32) -> Result<(), Error> {
 #[derive(Resource)]
 struct Random(oorandom::Rand32);
 
+/// The dice thrown in the scene, by their index in the state, and the entity of each.
+#[derive(Resource)]
+struct Thrown {
+    state: State,
+    dice: Vec<usize>,
+    entities: Vec<Entity>,
+    /// Whether the dice were thrown yet. Until then they only dropped into place and
+    /// their faces are no result.
+    started: bool,
+}
+
+/// The dice to throw: the one selected on the command line, else the hand from the
+/// config, else the first six-sided die.
+fn thrown_dice(
+    state: &mut State,
+    selected_die: Option<usize>,
+    settings: &Settings,
+) -> Result<Vec<usize>, String> {
+    let first_d6 = || {
+        state
+            .get_dice()
+            .iter()
+            .position(|die| die.get_range().len() == 6)
+    };
+    match (selected_die, &settings.hand) {
+        (Some(index), _) => {
+            state.clear_hand();
+            state.add_to_hand(index);
+        }
+        (None, Some(hand)) => state.set_hand(state.parse_roll(hand)?.0),
+        (None, None) => match first_d6() {
+            Some(index) => {
+                state.clear_hand();
+                state.add_to_hand(index);
+            }
+            None => return Err(tr(Message::NoDiceConfigured).to_string()),
+        },
+    }
+    let dice = state
+        .get_hand()
+        .get_entries()
+        .iter()
+        .flat_map(|(index, count)| std::iter::repeat_n(*index, *count as usize))
+        .collect::<Vec<_>>();
+    if dice.len() > MAX_DICE {
+        return Err(trf(Message::TooManyDice, &[&MAX_DICE]));
+    }
+    for index in &dice {
+        let die = &state.get_dice()[*index];
+        let faces = die.get_range().len();
+        if !shapes::has_shape(faces) {
+            return Err(trf(Message::NoShape, &[&die.get_name(), &faces]));
+        }
+    }
+    Ok(dice)
+}
+
+/// Where the die at `index` of `count` dice starts, around the middle of a square grid.
+fn slot(index: usize, count: usize) -> Vec2 {
+    let columns = (count as f32).sqrt().ceil().max(1.0) as usize;
+    let rows = count.div_ceil(columns);
+    Vec2::new(
+        (index % columns) as f32 - (columns - 1) as f32 / 2.0,
+        (index / columns) as f32 - (rows - 1) as f32 / 2.0,
+    ) * SPACING
+}
+
+/// Middle of the dice on the ground, how far the farthest is from it and the height
+/// of the highest die.
+struct Spread {
+    middle: Vec3,
+    radius: f32,
+    highest: f32,
+}
+
+impl Spread {
+    fn of<'t>(transforms: impl IntoIterator<Item = &'t Transform>) -> Option<Self> {
+        let positions = transforms
+            .into_iter()
+            .map(|transform| transform.translation)
+            .collect::<Vec<_>>();
+        let first = *positions.first()?;
+        let (low, high) = positions
+            .iter()
+            .fold((first, first), |(low, high), position| {
+                (low.min(*position), high.max(*position))
+            });
+        let middle = Vec3::new((low.x + high.x) / 2.0, 0.0, (low.z + high.z) / 2.0);
+        let radius = positions
+            .iter()
+            .map(|position| Vec2::new(position.x - middle.x, position.z - middle.z).length())
+            .fold(0.0, f32::max);
+        Some(Self {
+            middle,
+            radius,
+            highest: high.y,
+        })
+    }
+}
+
 /// A marker component for our wuerfel  so we can query them separately from the ground plane
 #[derive(Component)]
 struct Wuerfel;
 
+/// How long the wuerfel has been still and the face it came to rest on.
+#[derive(Component, Default)]
+struct Rest {
+    still_for: f32,
+    face: Option<u32>,
+}
+
+/// The value a die shows for every direction that can end up on top, in the die's
+/// own space.
+#[derive(Component)]
+struct Faces {
+    values: Vec<(Vec3, u32)>,
+}
+
+/// The overlay showing the rolled face.
+#[derive(Component)]
+struct ResultText;
+
 #[derive(Component)]
 struct Camera;
 
@@ -57,25 +285,70 @@ struct Light;
 fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    mut random: ResMut<Random>,
+    mut thrown: ResMut<Thrown>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
 ) {
-    let scene_handle = asset_server.load("wuerfel.glb#Scene0");
-    commands
-        .spawn((
-            SceneBundle {
-                scene: scene_handle,
-                transform: Transform::from_xyz(0.0, 5.0, 0.0),
-                ..Default::default()
-            },
-            Wuerfel,
-        ))
-        .insert(RigidBody::Dynamic)
-        .insert(Collider::cuboid(1.0, 1.0, 1.0))
-        .insert(Restitution::coefficient(0.9))
-        .insert(ExternalForce::default())
-        .insert(ExternalImpulse::default())
-        .insert(ColliderMassProperties::Mass(10.0));
+    let thrown = &mut *thrown;
+    for (slot_index, index) in thrown.dice.iter().enumerate() {
+        let die = &thrown.state.get_dice()[*index];
+        let (faces, first) = (die.get_range().len(), die.get_range().start);
+        let slot = slot(slot_index, thrown.dice.len());
+        // Dropped turned at random like a throw, so no face is favoured from the start.
+        let transform =
+            Transform::from_xyz(slot.x, 5.0, slot.y).with_rotation(random_rotation(&mut random.0));
+        let mut wuerfel = match shapes::die_shape(faces, first, die.get_color()) {
+            Some(shape) => {
+                let material = StandardMaterial {
+                    base_color_texture: Some(images.add(shape.texture)),
+                    perceptual_roughness: 0.6,
+                    ..default()
+                };
+                let mut wuerfel = commands.spawn((
+                    PbrBundle {
+                        mesh: meshes.add(shape.mesh),
+                        material: materials.add(material),
+                        transform,
+                        ..default()
+                    },
+                    Faces {
+                        values: shape.faces,
+                    },
+                ));
+                wuerfel.insert(shape.collider);
+                wuerfel
+            }
+            None => {
+                let values = D6_FACES
+                    .iter()
+                    .map(|(normal, pips)| (*normal, first + pips - 1))
+                    .collect();
+                let mut wuerfel = commands.spawn((
+                    SceneBundle {
+                        scene: asset_server.load("wuerfel.glb#Scene0"),
+                        transform,
+                        ..Default::default()
+                    },
+                    Faces { values },
+                ));
+                wuerfel.insert(Collider::cuboid(1.0, 1.0, 1.0));
+                wuerfel
+            }
+        };
+        wuerfel
+            .insert(Wuerfel)
+            .insert(RigidBody::Dynamic)
+            .insert(Restitution::coefficient(0.9))
+            .insert(ExternalForce::default())
+            .insert(ExternalImpulse::default())
+            .insert(Velocity::default())
+            .insert(Sleeping::default())
+            .insert(Rest::default())
+            .insert(ColliderMassProperties::Mass(10.0));
+        thrown.entities.push(wuerfel.id());
+    }
 
     commands.spawn((
         PointLightBundle {
@@ -118,72 +391,390 @@ fn setup(
     ));
 
     commands.spawn(
-        TextBundle::from_section("Press space to jump the wuerfel", TextStyle::default())
-            .with_style(Style {
-                position_type: PositionType::Absolute,
-                top: Val::Px(12.0),
-                left: Val::Px(12.0),
-                ..default()
-            }),
+        TextBundle::from_section(tr(Message::JumpHint), TextStyle::default()).with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        }),
     );
+
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 40.0,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(40.0),
+            left: Val::Px(12.0),
+            ..default()
+        }),
+        ResultText,
+    ));
 }
 
+/// Throws the dice together from a random spot near where they lie, each turned at
+/// random, with a common sideways push and their own spin scaled by the throw strength.
 fn toggle_jump(
     mut random: ResMut<Random>,
-    mut query: Query<&mut ExternalImpulse, With<Wuerfel>>,
+    settings: Res<Settings>,
+    mut thrown: ResMut<Thrown>,
+    mut query: Query<
+        (
+            &mut Transform,
+            &mut Velocity,
+            &mut ExternalImpulse,
+            &mut Sleeping,
+            &mut Rest,
+        ),
+        With<Wuerfel>,
+    >,
     keyboard: Res<ButtonInput<KeyCode>>,
 ) {
-    if keyboard.just_pressed(KeyCode::Space) {
-        for mut ext_impulse in &mut query {
-            let x = random.0.rand_float() * 10.0 * random.0.rand_float();
-            let z = random.0.rand_float() * 10.0 * random.0.rand_float();
-            ext_impulse.impulse = Vec3::new(x, 90.0, z);
-            ext_impulse.torque_impulse = Vec3::new(0.0, 0.0, 0.0);
+    if !keyboard.just_pressed(KeyCode::Space) {
+        return;
+    }
+    thrown.started = true;
+    let Some(spread) = Spread::of(query.iter().map(|(transform, ..)| transform)) else {
+        return;
+    };
+    let random = &mut random.0;
+    let strength = settings.throw_strength;
+    let launch = spread.middle
+        + Vec3::new(
+            signed_float(random) * LAUNCH_SPREAD,
+            LAUNCH_HEIGHT,
+            signed_float(random) * LAUNCH_SPREAD,
+        );
+    let direction = Vec2::from_angle(random.rand_float() * TAU);
+    let count = query.iter().len();
+    for (index, (mut transform, mut velocity, mut ext_impulse, mut sleeping, mut rest)) in
+        query.iter_mut().enumerate()
+    {
+        let slot = slot(index, count);
+        transform.translation = launch + Vec3::new(slot.x, 0.0, slot.y);
+        transform.rotation = random_rotation(random);
+        *velocity = Velocity::zero();
+        let sideways = direction * THROW_SIDEWAYS * random.rand_float();
+        let up = THROW_UP * (0.8 + 0.4 * random.rand_float());
+        ext_impulse.impulse = Vec3::new(sideways.x, up, sideways.y) * strength;
+        ext_impulse.torque_impulse = Vec3::new(
+            signed_float(random),
+            signed_float(random),
+            signed_float(random),
+        ) * THROW_SPIN
+            * strength;
+        // A sleeping body would ignore the impulse and still count as resting.
+        *sleeping = Sleeping::default();
+        *rest = Rest::default();
+    }
+}
+
+/// A float between -1 and 1.
+fn signed_float(random: &mut oorandom::Rand32) -> f32 {
+    random.rand_float() * 2.0 - 1.0
+}
+
+/// A rotation drawn uniformly from all rotations, following Shoemake's method.
+fn random_rotation(random: &mut oorandom::Rand32) -> Quat {
+    let (u1, u2, u3) = (
+        random.rand_float(),
+        random.rand_float(),
+        random.rand_float(),
+    );
+    let (a, b) = ((1.0 - u1).sqrt(), u1.sqrt());
+    Quat::from_xyzw(
+        a * (TAU * u2).sin(),
+        a * (TAU * u2).cos(),
+        b * (TAU * u3).sin(),
+        b * (TAU * u3).cos(),
+    )
+}
+
+/// Reads the face on top once the wuerfel stayed still for [`REST_TIME`] or fell asleep,
+/// and forgets it as soon as the wuerfel moves again.
+fn detect_rest(
+    time: Res<Time>,
+    mut query: Query<(&Velocity, &Sleeping, &Transform, &Faces, &mut Rest), With<Wuerfel>>,
+) {
+    for (velocity, sleeping, transform, faces, mut rest) in &mut query {
+        let still = velocity.linvel.length() < REST_SPEED && velocity.angvel.length() < REST_SPIN;
+        if sleeping.sleeping || still {
+            rest.still_for += time.delta_seconds();
+            if rest.face.is_none() && (sleeping.sleeping || rest.still_for >= REST_TIME) {
+                rest.face = Some(up_face(&faces.values, transform.rotation));
+            }
+        } else {
+            rest.still_for = 0.0;
+            rest.face = None;
+        }
+    }
+}
+
+/// The value of the direction in `faces` pointing most upwards when turned by `rotation`.
+fn up_face(faces: &[(Vec3, u32)], rotation: Quat) -> u32 {
+    faces
+        .iter()
+        .max_by(|(a, _), (b, _)| {
+            let a = (rotation * *a).dot(Vec3::Y);
+            let b = (rotation * *b).dot(Vec3::Y);
+            a.total_cmp(&b)
+        })
+        .map_or(0, |(_, value)| *value)
+}
+
+/// Lists the value of every die and their total once all of them came to rest after
+/// a throw.
+fn show_result(
+    thrown: Res<Thrown>,
+    rest_query: Query<Ref<Rest>>,
+    mut text_query: Query<&mut Text, With<ResultText>>,
+) {
+    if !thrown.started || !rest_query.iter().any(|rest| rest.is_changed()) {
+        return;
+    }
+    let faces = thrown
+        .entities
+        .iter()
+        .map(|entity| rest_query.get(*entity).ok().and_then(|rest| rest.face))
+        .collect::<Option<Vec<_>>>();
+    let dice = thrown.state.get_dice();
+    let result = match (faces, thrown.dice.as_slice()) {
+        (Some(faces), [index]) => trf(Message::RolledOn, &[&faces[0], &dice[*index].get_name()]),
+        (Some(faces), _) => {
+            let rolls = thrown.dice.iter().copied().zip(faces).collect();
+            thrown.state.describe_hand_roll(&HandRoll::new(rolls))
         }
+        (None, _) => tr(Message::Rolling).to_string(),
+    };
+    for mut text in &mut text_query {
+        text.sections[0].value.clone_from(&result);
     }
 }
 
-fn camera_follow_cube(
-    mut camera_query: Query<&mut Transform, With<Camera>>,
+/// Hovers over the middle of the dice, high enough to keep all of them in view and
+/// [`CAMERA_MARGIN`] above the highest one.
+fn camera_follow_dice(
+    time: Res<Time>,
+    mut camera_query: Query<(&mut Transform, &Projection), With<Camera>>,
     wuerfel_query: Query<&Transform, (With<Wuerfel>, Without<Camera>)>,
 ) {
-    for mut camera in &mut camera_query {
-        for wuerfel in &wuerfel_query {
-            camera.translation.x = wuerfel.translation.x;
-            camera.translation.z = wuerfel.translation.z;
-
-            //The camera should alway be at least 15.0 y from the ground.
-            //If the wuerfel is approaching the camera it should maintain at least 10.0 y distance from it.
-            let wuerfel_y = wuerfel.translation.y;
-            if wuerfel_y < 5.0 {
-                camera.translation.y = 15.0;
-            } else {
-                camera.translation.y = wuerfel_y + 10.0;
+    let Some(spread) = Spread::of(&wuerfel_query) else {
+        return;
+    };
+    for (mut camera, projection) in &mut camera_query {
+        // Half the narrower of the vertical and horizontal field of view.
+        let half_view = match projection {
+            Projection::Perspective(perspective) => {
+                let vertical = perspective.fov / 2.0;
+                vertical.min((vertical.tan() * perspective.aspect_ratio).atan())
             }
-        }
+            Projection::Orthographic(_) => FRAC_PI_4,
+        };
+        let height = ((spread.radius + CAMERA_MARGIN) / half_view.tan()).max(CAMERA_HEIGHT);
+        let target = Vec3::new(spread.middle.x, height, spread.middle.z);
+        let catch_up = (time.delta_seconds() * CAMERA_SPEED).min(1.0);
+        camera.translation = camera.translation.lerp(target, catch_up);
+        // A die flying towards the camera must not pass it.
+        camera.translation.y = camera.translation.y.max(spread.highest + CAMERA_MARGIN);
     }
 }
 
-fn ground_follow_cube(
+fn ground_follow_dice(
     mut ground_query: Query<&mut Transform, With<Ground>>,
     wuerfel_query: Query<&Transform, (With<Wuerfel>, Without<Ground>)>,
 ) {
+    let Some(spread) = Spread::of(&wuerfel_query) else {
+        return;
+    };
     for mut ground in &mut ground_query {
-        for wuerfel in &wuerfel_query {
-            ground.translation.x = wuerfel.translation.x;
-            ground.translation.z = wuerfel.translation.z;
-        }
+        ground.translation.x = spread.middle.x;
+        ground.translation.z = spread.middle.z;
     }
 }
 
-fn light_follow_cube(
+fn light_follow_dice(
     mut light_query: Query<&mut Transform, With<Light>>,
     wuerfel_query: Query<&Transform, (With<Wuerfel>, Without<Light>)>,
 ) {
+    let Some(spread) = Spread::of(&wuerfel_query) else {
+        return;
+    };
     for mut light in &mut light_query {
-        for wuerfel in &wuerfel_query {
-            light.translation.x = wuerfel.translation.x + 8.0;
-            light.translation.z = wuerfel.translation.z + 8.0;
+        light.translation.x = spread.middle.x + 8.0;
+        light.translation.z = spread.middle.z + 8.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::core::{Die, Symbol};
+
+    #[test]
+    fn unturned_wuerfel_shows_six() {
+        assert_eq!(up_face(&D6_FACES, Quat::IDENTITY), 6);
+    }
+
+    #[test]
+    fn every_face_can_end_up_on_top() {
+        let turns = [
+            (Quat::from_rotation_x(-FRAC_PI_2), 1),
+            (Quat::from_rotation_x(FRAC_PI_2), 3),
+            (Quat::from_rotation_x(FRAC_PI_2 * 2.0), 2),
+            (Quat::from_rotation_z(FRAC_PI_2), 4),
+            (Quat::from_rotation_z(-FRAC_PI_2), 5),
+        ];
+        for (rotation, face) in turns {
+            assert_eq!(up_face(&D6_FACES, rotation), face);
+        }
+    }
+
+    #[test]
+    fn random_rotations_are_normalized() {
+        let mut random = oorandom::Rand32::new(7);
+        for _ in 0..100 {
+            assert!(random_rotation(&mut random).is_normalized());
+        }
+    }
+
+    #[test]
+    fn throw_strength_from_config() {
+        let config = |text: &str| text.parse::<toml::Table>().unwrap();
+        let settings = Settings::from_config(&config("[3d]\nthrow_strength = 2")).unwrap();
+        assert_eq!(settings.throw_strength, 2.0);
+        let settings = Settings::from_config(&config("")).unwrap();
+        assert_eq!(settings.throw_strength, 1.0);
+        for invalid in ["0", "-1.5", "\"hard\""] {
+            let text = format!("[3d]\nthrow_strength = {}", invalid);
+            assert!(Settings::from_config(&config(&text)).is_err());
+        }
+        let settings = Settings::from_config(&config("[3d]\nhand = \"2d6\"")).unwrap();
+        assert_eq!(settings.hand.as_deref(), Some("2d6"));
+        assert!(Settings::from_config(&config("[3d]\nhand = 2")).is_err());
+    }
+
+    #[test]
+    fn thrown_dice_come_from_the_selection_or_the_hand() {
+        let mut state = State::default();
+        crate::setup_default_dice(&mut state);
+        let hand = |hand: &str| Settings {
+            hand: Some(hand.to_string()),
+            ..Settings::default()
+        };
+        assert_eq!(
+            thrown_dice(&mut state, None, &hand("2d6 + d20")),
+            Ok(vec![1, 1, 3])
+        );
+        assert_eq!(thrown_dice(&mut state, Some(2), &hand("2d6")), Ok(vec![2]));
+        assert_eq!(
+            thrown_dice(&mut state, None, &Settings::default()),
+            Ok(vec![1])
+        );
+        assert!(thrown_dice(&mut state, None, &hand("d7")).is_err());
+        assert!(thrown_dice(&mut state, None, &hand("25d6")).is_err());
+    }
+
+    #[test]
+    fn dice_without_a_shape_are_not_thrown() {
+        let mut state = State::default();
+        let mut d3 = Die::new("D3".to_string());
+        for (index, symbol) in Symbol::parse_faces("1-3").unwrap().into_iter().enumerate() {
+            d3.insert_symbol(symbol, index);
         }
+        state.add_die(d3);
+        assert!(thrown_dice(&mut state, Some(0), &Settings::default()).is_err());
+    }
+
+    #[test]
+    fn slots_keep_the_dice_apart() {
+        for count in 1..=MAX_DICE {
+            let slots = (0..count)
+                .map(|index| slot(index, count))
+                .collect::<Vec<_>>();
+            for (index, a) in slots.iter().enumerate() {
+                assert!(a.length() <= SPACING * count as f32);
+                for b in &slots[index + 1..] {
+                    assert!(a.distance(*b) >= SPACING - f32::EPSILON);
+                }
+            }
+        }
+        assert_eq!(slot(0, 1), Vec2::ZERO);
+    }
+
+    #[test]
+    fn tilted_wuerfel_shows_the_face_closest_to_up() {
+        let rotation = Quat::from_rotation_z(0.3) * Quat::from_rotation_x(-0.2);
+        assert_eq!(up_face(&D6_FACES, rotation), 6);
+    }
+
+    /// A scene with one sleeping D6 and the result text, without physics or rendering.
+    fn scene() -> App {
+        let mut state = State::default();
+        crate::setup_default_dice(&mut state);
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<ButtonInput<KeyCode>>()
+            .insert_resource(Random(oorandom::Rand32::new(7)))
+            .insert_resource(Settings::default())
+            .add_systems(Update, (toggle_jump, detect_rest, show_result).chain());
+        let wuerfel = app
+            .world_mut()
+            .spawn((
+                Wuerfel,
+                Transform::default(),
+                Velocity::zero(),
+                ExternalImpulse::default(),
+                Sleeping {
+                    sleeping: true,
+                    ..Sleeping::default()
+                },
+                Rest::default(),
+                Faces {
+                    values: D6_FACES.to_vec(),
+                },
+            ))
+            .id();
+        app.world_mut()
+            .spawn((Text::from_section("", TextStyle::default()), ResultText));
+        app.insert_resource(Thrown {
+            state,
+            dice: vec![1],
+            entities: vec![wuerfel],
+            started: false,
+        });
+        app
+    }
+
+    fn result_text(app: &mut App) -> String {
+        let mut query = app.world_mut().query_filtered::<&Text, With<ResultText>>();
+        query.single(app.world()).sections[0].value.clone()
+    }
+
+    #[test]
+    fn no_result_before_the_first_throw() {
+        let mut app = scene();
+        app.update();
+        app.update();
+        assert_eq!(result_text(&mut app), "");
+    }
+
+    #[test]
+    fn a_throw_wakes_the_wuerfel_and_waits_for_it() {
+        let mut app = scene();
+        app.update();
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::Space);
+        app.update();
+        let mut sleeping = app.world_mut().query::<&Sleeping>();
+        assert!(!sleeping.single(app.world()).sleeping);
+        assert_eq!(result_text(&mut app), tr(Message::Rolling));
     }
 }
@@ -0,0 +1,523 @@
+use std::f32::consts::{PI, TAU};
+
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{Indices, PrimitiveTopology},
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+use bevy_rapier3d::prelude::Collider;
+
+/// Outward normal of every face of `wuerfel.glb` in the cube's own space, with the
+/// number of pips painted on it.
+pub(super) const D6_FACES: [(Vec3, u32); 6] = [
+    (Vec3::Z, 1),
+    (Vec3::NEG_Y, 2),
+    (Vec3::NEG_Z, 3),
+    (Vec3::X, 4),
+    (Vec3::NEG_X, 5),
+    (Vec3::Y, 6),
+];
+/// Distance below which two points or planes are the same.
+const EPSILON: f32 = 1e-4;
+/// Side of the square each face takes up in the texture, in pixels.
+const CELL: usize = 128;
+/// Digits 0 to 9 in a 3×5 pixel font, one row per byte with the left pixel in bit 2.
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+const DEFAULT_FILL: [u8; 3] = [240, 234, 214];
+
+/// Mesh, texture and collider of a die that is not a cube, and the value it shows
+/// for every direction that can end up on top.
+pub(super) struct DieShape {
+    pub(super) mesh: Mesh,
+    pub(super) texture: Image,
+    pub(super) collider: Collider,
+    /// Direction in the die's own space with the value shown when it points up.
+    pub(super) faces: Vec<(Vec3, u32)>,
+}
+
+/// A convex polyhedron centered on the origin.
+struct Polyhedron {
+    vertices: Vec<Vec3>,
+    faces: Vec<Face>,
+}
+
+struct Face {
+    normal: Vec3,
+    /// Indices into the vertices, counterclockwise seen from outside.
+    corners: Vec<usize>,
+}
+
+/// Whether dice with `faces` faces can be thrown, as the cube or a [`die_shape`].
+pub(super) fn has_shape(faces: usize) -> bool {
+    matches!(faces, 4 | 6 | 8 | 10 | 12 | 20)
+}
+
+/// The die with `faces` faces whose lowest value is `first`, or `None` for cubes and
+/// face counts without a regular shape.
+///
+/// Four-sided dice show their value at the corner pointing up, like real ones; every
+/// other die shows it on the face on top.
+pub(super) fn die_shape(faces: usize, first: u32, color: Option<[u8; 3]>) -> Option<DieShape> {
+    let (vertices, radius) = match faces {
+        4 => (tetrahedron(), 1.8),
+        8 => (octahedron(), 1.5),
+        10 => (trapezohedron(), 1.4),
+        12 => (dodecahedron(), 1.4),
+        20 => (icosahedron(), 1.4),
+        _ => return None,
+    };
+    let farthest = vertices
+        .iter()
+        .map(|vertex| vertex.length())
+        .fold(0.0, f32::max);
+    let vertices = vertices
+        .into_iter()
+        .map(|vertex| vertex * radius / farthest)
+        .collect::<Vec<_>>();
+    let polyhedron = Polyhedron::hull(vertices);
+    if faces == 4 {
+        let values = (first..).take(4).collect::<Vec<_>>();
+        let labels = polyhedron
+            .faces
+            .iter()
+            .map(|face| face.corners.iter().map(|corner| values[*corner]).collect())
+            .collect::<Vec<_>>();
+        let faces = polyhedron
+            .vertices
+            .iter()
+            .zip(values)
+            .map(|(vertex, value)| (vertex.normalize(), value))
+            .collect();
+        return Some(polyhedron.into_die(labels, Placement::Corners, faces, color));
+    }
+    let values = polyhedron
+        .face_numbers()
+        .into_iter()
+        .map(|number| first + number - 1)
+        .collect::<Vec<_>>();
+    let faces = polyhedron
+        .faces
+        .iter()
+        .zip(&values)
+        .map(|(face, value)| (face.normal, *value))
+        .collect();
+    let labels = values.iter().map(|value| vec![*value]).collect();
+    Some(polyhedron.into_die(labels, Placement::Center, faces, color))
+}
+
+/// Where the values of a face are drawn.
+#[derive(Clone, Copy)]
+enum Placement {
+    /// One value in the middle, upright towards the corner farthest from the middle.
+    Center,
+    /// One value in every corner, upright towards it.
+    Corners,
+}
+
+impl Polyhedron {
+    /// The convex hull of `vertices`, which surround the origin.
+    ///
+    /// Every plane through three vertices that has no vertex in front of it is a face.
+    fn hull(vertices: Vec<Vec3>) -> Self {
+        let mut faces: Vec<Face> = Vec::new();
+        let count = vertices.len();
+        for a in 0..count {
+            for b in a + 1..count {
+                for c in b + 1..count {
+                    let normal = (vertices[b] - vertices[a]).cross(vertices[c] - vertices[a]);
+                    if normal.length() < EPSILON {
+                        continue;
+                    }
+                    let mut normal = normal.normalize();
+                    if normal.dot(vertices[a]) < 0.0 {
+                        normal = -normal;
+                    }
+                    let offset = normal.dot(vertices[a]);
+                    let outside = vertices
+                        .iter()
+                        .any(|vertex| normal.dot(*vertex) > offset + EPSILON);
+                    let known = faces
+                        .iter()
+                        .any(|face| face.normal.dot(normal) > 1.0 - EPSILON);
+                    if outside || known {
+                        continue;
+                    }
+                    let corners = (0..count)
+                        .filter(|index| (normal.dot(vertices[*index]) - offset).abs() < EPSILON)
+                        .collect();
+                    faces.push(Face {
+                        normal,
+                        corners: counterclockwise(&vertices, corners, normal),
+                    });
+                }
+            }
+        }
+        Self { vertices, faces }
+    }
+
+    /// Numbers from 1 with opposite faces adding up to one more than the face count,
+    /// as on real dice.
+    fn face_numbers(&self) -> Vec<u32> {
+        let count = self.faces.len() as u32;
+        let mut numbers = vec![0; self.faces.len()];
+        let mut next = 1;
+        for index in 0..self.faces.len() {
+            if numbers[index] != 0 {
+                continue;
+            }
+            numbers[index] = next;
+            let normal = self.faces[index].normal;
+            let opposite = self
+                .faces
+                .iter()
+                .position(|face| face.normal.dot(normal) < -1.0 + EPSILON);
+            if let Some(opposite) = opposite.filter(|opposite| numbers[*opposite] == 0) {
+                numbers[opposite] = count + 1 - next;
+            }
+            next += 1;
+            // Skip numbers already given to opposite faces.
+            while numbers.contains(&next) {
+                next += 1;
+            }
+        }
+        numbers
+    }
+
+    /// Flat shaded mesh with one texture cell per face showing its `labels`.
+    fn into_die(
+        self,
+        labels: Vec<Vec<u32>>,
+        placement: Placement,
+        faces: Vec<(Vec3, u32)>,
+        color: Option<[u8; 3]>,
+    ) -> DieShape {
+        let columns = (self.faces.len() as f32).sqrt().ceil() as usize;
+        let rows = self.faces.len().div_ceil(columns);
+        let fill = color.unwrap_or(DEFAULT_FILL);
+        let ink = contrasting_ink(fill);
+        let mut atlas = Atlas::new(columns * CELL, rows * CELL, fill);
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut uvs = Vec::new();
+        let mut indices = Vec::new();
+        for (index, (face, labels)) in self.faces.iter().zip(&labels).enumerate() {
+            let corners = face
+                .corners
+                .iter()
+                .map(|corner| self.vertices[*corner])
+                .collect::<Vec<_>>();
+            let center = corners.iter().sum::<Vec3>() / corners.len() as f32;
+            // Upright towards the corner farthest from the middle, the tip of a kite.
+            let top = corners
+                .iter()
+                .copied()
+                .reduce(|top, corner| {
+                    if corner.distance(center) > top.distance(center) + EPSILON {
+                        corner
+                    } else {
+                        top
+                    }
+                })
+                .unwrap_or(center);
+            let up = (top - center).normalize();
+            let right = up.cross(face.normal);
+            let flat = corners
+                .iter()
+                .map(|corner| Vec2::new((*corner - center).dot(right), (*corner - center).dot(up)))
+                .collect::<Vec<_>>();
+            let extent = flat.iter().map(|point| point.length()).fold(0.0, f32::max);
+            let cell_center = Vec2::new(
+                ((index % columns) * CELL + CELL / 2) as f32,
+                ((index / columns) * CELL + CELL / 2) as f32,
+            );
+            let scale = CELL as f32 * 0.46 / extent;
+            // Texture rows go down while the face's up goes up.
+            let pixel = |point: Vec2| cell_center + Vec2::new(point.x, -point.y) * scale;
+            match placement {
+                Placement::Center => {
+                    let inner = flat
+                        .iter()
+                        .zip(flat.iter().cycle().skip(1))
+                        .map(|(a, b)| a.perp_dot(*b).abs() / a.distance(*b))
+                        .fold(f32::MAX, f32::min);
+                    atlas.number(
+                        cell_center,
+                        Vec2::NEG_Y,
+                        inner * scale * 0.9,
+                        labels[0],
+                        ink,
+                    );
+                }
+                Placement::Corners => {
+                    for (point, label) in flat.iter().zip(labels) {
+                        let toward = Vec2::new(point.x, -point.y).normalize();
+                        let position = pixel(*point * 0.55);
+                        atlas.number(position, toward, extent * scale * 0.3, *label, ink);
+                    }
+                }
+            }
+            let start = positions.len() as u32;
+            for (corner, point) in corners.iter().zip(&flat) {
+                let uv = pixel(*point) / Vec2::new(atlas.width as f32, atlas.height as f32);
+                positions.push(corner.to_array());
+                normals.push(face.normal.to_array());
+                uvs.push(uv.to_array());
+            }
+            for corner in 1..corners.len() as u32 - 1 {
+                indices.extend([start, start + corner, start + corner + 1]);
+            }
+        }
+        let mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_indices(Indices::U32(indices));
+        let collider = Collider::convex_hull(&self.vertices)
+            .expect("The vertices of a die should span a volume");
+        DieShape {
+            mesh,
+            texture: atlas.into_image(),
+            collider,
+            faces,
+        }
+    }
+}
+
+/// Orders the `corners` of a face around its middle, counterclockwise seen from outside.
+fn counterclockwise(vertices: &[Vec3], mut corners: Vec<usize>, normal: Vec3) -> Vec<usize> {
+    let center =
+        corners.iter().map(|corner| vertices[*corner]).sum::<Vec3>() / corners.len() as f32;
+    let first = (vertices[corners[0]] - center).normalize();
+    let second = normal.cross(first);
+    let angle = |corner: &usize| {
+        let offset = vertices[*corner] - center;
+        offset.dot(second).atan2(offset.dot(first))
+    };
+    corners.sort_by(|a, b| angle(a).total_cmp(&angle(b)));
+    corners
+}
+
+/// Black on light and white on dark dice.
+fn contrasting_ink([r, g, b]: [u8; 3]) -> [u8; 3] {
+    if 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32 > 140.0 {
+        [20, 20, 30]
+    } else {
+        [250, 250, 250]
+    }
+}
+
+/// The RGBA pixels of the face texture.
+struct Atlas {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Atlas {
+    fn new(width: usize, height: usize, [r, g, b]: [u8; 3]) -> Self {
+        Self {
+            width,
+            height,
+            pixels: [r, g, b, 255].repeat(width * height),
+        }
+    }
+
+    /// Draws `value` centered on `center`, `size` pixels high and upright towards `up`.
+    /// Sixes and nines are underlined to tell them apart.
+    fn number(&mut self, center: Vec2, up: Vec2, size: f32, value: u32, ink: [u8; 3]) {
+        let digits = value
+            .to_string()
+            .bytes()
+            .map(|digit| DIGITS[(digit - b'0') as usize])
+            .collect::<Vec<_>>();
+        let underlined = value == 6 || value == 9;
+        let columns = digits.len() * 4 - 1;
+        let rows = if underlined { 7 } else { 5 };
+        let unit = size / 5.0;
+        let right = Vec2::new(-up.y, up.x);
+        let reach = (columns.max(rows) as f32 * unit).ceil() as i32;
+        for y in -reach..=reach {
+            for x in -reach..=reach {
+                let offset = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let column = offset.dot(right) / unit + columns as f32 / 2.0;
+                let row = 2.5 - offset.dot(up) / unit;
+                if column < 0.0 || row < 0.0 {
+                    continue;
+                }
+                let (column, row) = (column as usize, row as usize);
+                let lit = match row {
+                    0..=4 if column < columns && column % 4 != 3 => {
+                        digits[column / 4][row] & (0b100 >> (column % 4)) != 0
+                    }
+                    6 => underlined && column < columns,
+                    _ => false,
+                };
+                if lit {
+                    self.put(center.x as i32 + x, center.y as i32 + y, ink);
+                }
+            }
+        }
+    }
+
+    fn put(&mut self, x: i32, y: i32, [r, g, b]: [u8; 3]) {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return;
+        }
+        let start = (y as usize * self.width + x as usize) * 4;
+        self.pixels[start..start + 4].copy_from_slice(&[r, g, b, 255]);
+    }
+
+    fn into_image(self) -> Image {
+        Image::new(
+            Extent3d {
+                width: self.width as u32,
+                height: self.height as u32,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            self.pixels,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        )
+    }
+}
+
+fn tetrahedron() -> Vec<Vec3> {
+    vec![
+        Vec3::new(1.0, 1.0, 1.0),
+        Vec3::new(1.0, -1.0, -1.0),
+        Vec3::new(-1.0, 1.0, -1.0),
+        Vec3::new(-1.0, -1.0, 1.0),
+    ]
+}
+
+fn octahedron() -> Vec<Vec3> {
+    vec![
+        Vec3::X,
+        Vec3::NEG_X,
+        Vec3::Y,
+        Vec3::NEG_Y,
+        Vec3::Z,
+        Vec3::NEG_Z,
+    ]
+}
+
+/// The ten-sided die: two apexes and a zigzag ring of ten corners between them, high
+/// enough apart that every kite is flat.
+fn trapezohedron() -> Vec<Vec3> {
+    let zigzag = 0.105;
+    let cos = (PI / 5.0).cos();
+    let apex = zigzag * (1.0 + cos) / (1.0 - cos);
+    let mut vertices = vec![Vec3::new(0.0, apex, 0.0), Vec3::new(0.0, -apex, 0.0)];
+    for corner in 0..10 {
+        let angle = TAU * corner as f32 / 10.0;
+        let height = if corner % 2 == 0 { zigzag } else { -zigzag };
+        vertices.push(Vec3::new(angle.cos(), height, angle.sin()));
+    }
+    vertices
+}
+
+fn dodecahedron() -> Vec<Vec3> {
+    let phi = (1.0 + 5f32.sqrt()) / 2.0;
+    let mut vertices = Vec::new();
+    for x in [-1.0, 1.0] {
+        for y in [-1.0, 1.0] {
+            for z in [-1.0, 1.0] {
+                vertices.push(Vec3::new(x, y, z));
+            }
+        }
+    }
+    for a in [-1.0, 1.0] {
+        for b in [-1.0, 1.0] {
+            vertices.push(Vec3::new(0.0, a / phi, b * phi));
+            vertices.push(Vec3::new(a / phi, b * phi, 0.0));
+            vertices.push(Vec3::new(a * phi, 0.0, b / phi));
+        }
+    }
+    vertices
+}
+
+fn icosahedron() -> Vec<Vec3> {
+    let phi = (1.0 + 5f32.sqrt()) / 2.0;
+    let mut vertices = Vec::new();
+    for a in [-1.0, 1.0] {
+        for b in [-1.0, 1.0] {
+            vertices.push(Vec3::new(0.0, a, b * phi));
+            vertices.push(Vec3::new(a, b * phi, 0.0));
+            vertices.push(Vec3::new(a * phi, 0.0, b));
+        }
+    }
+    vertices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_shape_has_as_many_faces_as_the_die() {
+        for (faces, corners) in [(4, 3), (8, 3), (10, 4), (12, 5), (20, 3)] {
+            let shape = die_shape(faces, 1, None).unwrap();
+            assert_eq!(shape.faces.len(), faces);
+            let mut values = shape
+                .faces
+                .iter()
+                .map(|(_, value)| *value)
+                .collect::<Vec<_>>();
+            values.sort();
+            assert_eq!(values, (1..=faces as u32).collect::<Vec<_>>());
+            let vertex_count = shape.mesh.count_vertices();
+            assert_eq!(vertex_count, faces * corners);
+        }
+        assert!(die_shape(6, 1, None).is_none());
+        assert!(die_shape(7, 1, None).is_none());
+    }
+
+    #[test]
+    fn opposite_faces_add_up() {
+        for faces in [8, 10, 12, 20] {
+            let shape = die_shape(faces, 1, None).unwrap();
+            for (normal, value) in &shape.faces {
+                let (_, opposite) = shape
+                    .faces
+                    .iter()
+                    .find(|(other, _)| other.dot(*normal) < -1.0 + EPSILON)
+                    .unwrap();
+                assert_eq!(value + opposite, faces as u32 + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn values_start_at_the_lowest_face() {
+        let shape = die_shape(10, 0, None).unwrap();
+        let lowest = shape.faces.iter().map(|(_, value)| *value).min();
+        assert_eq!(lowest, Some(0));
+    }
+
+    #[test]
+    fn faces_wind_counterclockwise_from_outside() {
+        let polyhedron = Polyhedron::hull(trapezohedron());
+        for face in &polyhedron.faces {
+            let [a, b, c] = [0, 1, 2].map(|corner| polyhedron.vertices[face.corners[corner]]);
+            assert!((b - a).cross(c - a).dot(face.normal) > 0.0);
+        }
+    }
+}
@@ -1,36 +1,175 @@
-use std::io;
+use std::{
+    cell::Cell,
+    io,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::{Duration, Instant},
+};
 
 use oorandom::Rand32;
 use ratatui::{
-    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
-    layout::Alignment,
+    buffer::Buffer,
+    crossterm::{
+        event::{
+            self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+            MouseButton, MouseEvent, MouseEventKind,
+        },
+        execute,
+    },
+    layout::{self, Alignment, Constraint, Layout, Rect},
     style::Stylize,
-    symbols::border,
-    text::{Line, Text},
+    symbols,
+    text::{Line, Span, Text},
     widgets::{
         block::{Position, Title},
-        Block, Paragraph, Widget,
+        Axis, Chart, Dataset, GraphType, LegendPosition, List, ListItem, ListState, Paragraph, Row,
+        StatefulWidget, Table, Widget, Wrap,
     },
     DefaultTerminal, Frame,
 };
 
-use crate::{core::Error, State};
+use crate::{
+    config,
+    core::{Error, Session},
+    history::{DieStatistics, History},
+    locale::{tr, trf, Message},
+    roll::{Outcome, RollMode, RollOptions, RollResult},
+    Die, DieLookup, State, Symbol,
+};
+
+use bindings::{Action, KeyBindings};
+use editor::{Editor, EditorAction};
+use theme::Theme;
 
-struct App<'a> {
-    state: State<'a>,
-    current_index: Option<usize>,
-    current_range: std::ops::Range<usize>,
-    current_die_roll: Option<u32>,
-    random: Rand32,
+mod bindings;
+mod editor;
+mod face;
+#[cfg(test)]
+mod tests;
+mod theme;
+
+/// Interval between ticks, which drive the roll animation, unless set with `--tick-rate`.
+pub(crate) const DEFAULT_TICK_RATE: Duration = Duration::from_millis(60);
+/// How long a die tumbles before its result is revealed.
+const ANIMATION_DURATION: Duration = Duration::from_millis(720);
+/// A simulation rolls the selected die this many times per batch, pausing between batches.
+const SIMULATION_BATCH_SIZE: usize = 50;
+const SIMULATION_BATCHES: usize = 20;
+const SIMULATION_PAUSE: Duration = Duration::from_millis(100);
+/// Dice shown in the statistics footer before older ones are cut off.
+const MAX_STATISTICS_ROWS: usize = 4;
+/// Below this width the dice list and history are hidden.
+const MIN_SIDE_PANES_WIDTH: u16 = 80;
+/// Two clicks on the same die within this interval roll it.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Key bindings and theme from the `[tui.keys]` and `[tui.theme]` config sections.
+#[derive(Default)]
+pub(crate) struct Settings {
+    key_bindings: KeyBindings,
+    theme: Theme,
+}
+
+impl Settings {
+    pub(crate) fn from_config(config: &toml::Table) -> Result<Settings, String> {
+        Ok(Settings {
+            key_bindings: KeyBindings::from_config(config::section(config, "tui.keys"))?,
+            theme: Theme::from_config(config::section(config, "tui.theme"))?,
+        })
+    }
+}
+
+/// Updates sent to the TUI from background work through [`App::sender`].
+pub(crate) enum AppMessage {
+    /// Rolls made outside of the event loop, recorded in the history.
+    Rolls { die: String, values: Vec<u32> },
+    /// Text shown below the dice.
+    Status(String),
+}
+
+struct App {
+    session: Session,
+    /// Die roll that is still tumbling, its result is not shown yet.
+    animation: Option<Animation>,
+    /// Text typed into the search box while it is open.
+    search: Option<String>,
+    /// Dice editor popup, which takes all key events while it is open.
+    editor: Option<Editor>,
+    /// Result of the last search or copy, shown below the dice.
+    feedback: Option<String>,
+    history: History,
+    key_bindings: KeyBindings,
+    theme: Theme,
+    /// Selected history entry, counted from the newest.
+    history_selected: usize,
+    tick_rate: Duration,
+    sender: Sender<AppMessage>,
+    messages: Receiver<AppMessage>,
+    /// Time and die of the last click on the dice list, to detect double clicks.
+    last_click: Option<(Instant, usize)>,
+    /// Where the dice list and history were last drawn, to map mouse events onto them.
+    dice_list_area: Cell<Rect>,
+    dice_list_offset: Cell<usize>,
+    history_area: Cell<Rect>,
+    /// Opened on the first copy and kept open, as on Linux the copied text is only
+    /// served while the clipboard that set it is alive.
+    clipboard: Option<arboard::Clipboard>,
     exit: bool,
 }
 
-impl<'a> App<'a> {
+/// The last die roll of the session while it is still tumbling.
+struct Animation {
+    die_index: usize,
+    /// Random face shown while tumbling.
+    face: u32,
+    ticks_left: u32,
+}
+
+impl App {
+    /// Creates the app without touching the terminal, so it can also be drawn onto a
+    /// test backend. All randomness comes from `random`.
+    fn new(
+        state: State,
+        selected_die: Option<usize>,
+        roll_mode: RollMode,
+        tick_rate: Duration,
+        settings: Settings,
+        random: Rand32,
+    ) -> Self {
+        let (sender, messages) = mpsc::channel();
+        Self {
+            session: Session::new(state, selected_die, roll_mode, random),
+            animation: None,
+            search: None,
+            editor: None,
+            feedback: None,
+            history: History::default(),
+            key_bindings: settings.key_bindings,
+            theme: settings.theme,
+            history_selected: 0,
+            tick_rate,
+            sender,
+            messages,
+            last_click: None,
+            dice_list_area: Cell::default(),
+            dice_list_offset: Cell::default(),
+            history_area: Cell::default(),
+            clipboard: None,
+            exit: false,
+        }
+    }
+
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<(), Error> {
         let mut exit = self.exit;
+        let mut last_tick = Instant::now();
         while !exit {
             terminal.draw(|frame| self.draw(frame))?;
-            self.handle_events()?;
+            self.handle_events(self.tick_rate.saturating_sub(last_tick.elapsed()))?;
+            self.handle_messages();
+            if last_tick.elapsed() >= self.tick_rate {
+                self.on_tick();
+                last_tick = Instant::now();
+            }
             exit = self.exit;
         }
         Ok(())
@@ -40,147 +179,799 @@ impl<'a> App<'a> {
         frame.render_widget(self, frame.area());
     }
 
-    fn handle_events(&mut self) -> io::Result<()> {
+    /// Waits up to `timeout` for an event so ticks keep running without input.
+    fn handle_events(&mut self, timeout: Duration) -> io::Result<()> {
+        if !event::poll(timeout)? {
+            return Ok(());
+        }
         match event::read()? {
             // it's important to check that the event is a key press event as
             // crossterm also emits key release and repeat events on Windows.
             Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                 self.handle_key_event(key_event)
             }
+            Event::Mouse(mouse_event) => self.handle_mouse_event(mouse_event),
             _ => {}
         };
         Ok(())
     }
 
+    /// A sender for background work to update the TUI with.
+    fn sender(&self) -> Sender<AppMessage> {
+        self.sender.clone()
+    }
+
+    fn handle_messages(&mut self) {
+        while let Ok(message) = self.messages.try_recv() {
+            match message {
+                AppMessage::Rolls { die, values } => {
+                    for value in values {
+                        self.history.record(&die, value);
+                    }
+                }
+                AppMessage::Status(status) => self.feedback = Some(status),
+            }
+        }
+    }
+
+    fn on_tick(&mut self) {
+        let Some(animation) = self.animation.as_mut() else {
+            return;
+        };
+        animation.ticks_left = animation.ticks_left.saturating_sub(1);
+        if animation.ticks_left == 0 {
+            self.settle_animation();
+        } else if let Some(die) = self.session.state().get_dice().get(animation.die_index) {
+            let range = die.get_range();
+            animation.face = self.session.random().rand_range(range);
+        }
+    }
+
+    /// Reveals the result of a tumbling die and records it.
+    fn settle_animation(&mut self) {
+        if self.animation.take().is_none() {
+            return;
+        }
+        let Some((index, result)) = self.session.die_roll() else {
+            return;
+        };
+        if let Some(die) = self.session.state().get_dice().get(index) {
+            self.history.record_roll(die.get_name(), &result);
+            self.history_selected = 0;
+        }
+    }
+
     fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if let Some(editor) = self.editor.as_mut() {
+            match editor.handle_key(key_event.code) {
+                EditorAction::Continue => {}
+                EditorAction::Save => self.save_editor(),
+                EditorAction::Cancel => self.editor = None,
+            }
+            return;
+        }
+        if self.search.is_some() {
+            self.handle_search_key_event(key_event);
+            return;
+        }
+        match self.key_bindings.action(key_event.code) {
+            Some(action) => self.perform(action),
+            None => {
+                if let KeyCode::Char(digit @ '1'..='9') = key_event.code {
+                    self.session.select(digit as usize - '1' as usize);
+                }
+            }
+        }
+    }
+
+    fn perform(&mut self, action: Action) {
+        match action {
+            Action::Quit => self.exit(),
+            Action::Previous => self.session.previous_die(),
+            Action::Next => self.session.next_die(),
+            Action::Roll => self.roll_die(),
+            Action::AddToHand => self.session.add_to_hand(),
+            Action::RemoveFromHand => self.session.remove_from_hand(),
+            Action::ClearHand => self.session.clear_hand(),
+            Action::RollHand => self.roll_hand(),
+            Action::IncreaseModifier => self.session.step_modifier(1),
+            Action::DecreaseModifier => self.session.step_modifier(-1),
+            Action::CycleMode => self.session.cycle_mode(),
+            Action::ToggleTarget => self.session.toggle_target(),
+            Action::IncreaseTarget => self.session.step_target(1),
+            Action::DecreaseTarget => self.session.step_target(-1),
+            Action::HistoryUp => self.history_selected = self.history_selected.saturating_sub(1),
+            Action::HistoryDown => self.select_older_entry(),
+            Action::ClearHistory => {
+                self.history.clear();
+                self.history_selected = 0;
+            }
+            Action::CopyEntry => self.copy_history_entry(),
+            Action::Simulate => self.simulate(),
+            Action::NewDie => self.editor = Some(Editor::new_die()),
+            Action::EditDie => {
+                self.editor = self.session.selected().and_then(|index| {
+                    let die = self.session.selected_die()?;
+                    Some(Editor::edit(index, die))
+                })
+            }
+            Action::DuplicateDie => self.duplicate_die(),
+            Action::DeleteDie => self.delete_die(),
+            Action::Search => {
+                self.search = Some(String::new());
+                self.feedback = None;
+            }
+        }
+    }
+
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        if self.editor.is_some() {
+            return;
+        }
+        let position = layout::Position::new(mouse_event.column, mouse_event.row);
+        let in_dice_list = self.dice_list_area.get().contains(position);
+        let in_history = self.history_area.get().contains(position);
+        match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) if in_dice_list => {
+                let Some(index) = self.dice_list_index(mouse_event.row) else {
+                    return;
+                };
+                let now = Instant::now();
+                let double_click = self.last_click.is_some_and(|(time, clicked)| {
+                    clicked == index && now.duration_since(time) <= DOUBLE_CLICK
+                });
+                self.session.select(index);
+                if double_click {
+                    self.last_click = None;
+                    self.roll_die();
+                } else {
+                    self.last_click = Some((now, index));
+                }
+            }
+            MouseEventKind::ScrollUp if in_dice_list => self.session.previous_die(),
+            MouseEventKind::ScrollDown if in_dice_list => self.session.next_die(),
+            MouseEventKind::ScrollUp if in_history => {
+                self.history_selected = self.history_selected.saturating_sub(1)
+            }
+            MouseEventKind::ScrollDown if in_history => self.select_older_entry(),
+            _ => {}
+        }
+    }
+
+    /// Index of the die shown in terminal `row` of the dice list.
+    fn dice_list_index(&self, row: u16) -> Option<usize> {
+        let area = self.dice_list_area.get();
+        // The first row holds the border of the list.
+        let line = row.checked_sub(area.y + 1)? as usize;
+        let index = self.dice_list_offset.get() + line;
+        (index < self.session.state().get_dice().len()).then_some(index)
+    }
+
+    fn select_older_entry(&mut self) {
+        let last = self.history.get_entries().len().saturating_sub(1);
+        self.history_selected = (self.history_selected + 1).min(last);
+    }
+
+    fn handle_search_key_event(&mut self, key_event: KeyEvent) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
         match key_event.code {
-            KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => self.exit(),
-            KeyCode::Left => self.previous_die(),
-            KeyCode::Right => self.next_die(),
-            KeyCode::Enter => self.roll_die(),
+            KeyCode::Esc => {
+                self.search = None;
+                self.feedback = None;
+            }
+            KeyCode::Enter => self.submit_search(),
+            KeyCode::Backspace => {
+                search.pop();
+            }
+            KeyCode::Char(c) => search.push(c),
             _ => {}
         }
     }
 
+    fn submit_search(&mut self) {
+        let Some(search) = self.search.as_deref() else {
+            return;
+        };
+        let lookup = self.session.state().find_die(search);
+        self.feedback = Some(self.session.state().describe_lookup(&lookup));
+        if let DieLookup::Found(index) = lookup {
+            self.session.select(index);
+            self.search = None;
+        }
+    }
+
     fn exit(&mut self) {
         self.exit = true;
     }
 
-    fn previous_die(&mut self) {
-        if self.current_range.len() == 0 {
+    /// Validates the editor input and applies it to the state, keeping the editor
+    /// open with the error otherwise.
+    fn save_editor(&mut self) {
+        let Some(editor) = self.editor.as_mut() else {
             return;
-        }
-        if self.current_index.is_none() && self.current_range.len() != 0 {
-            self.current_index = Some(self.current_range.end);
-        } else {
-            self.current_index = self.current_index.map(|index| {
-                if index == 0 {
-                    self.current_range.end
-                } else {
-                    index - 1
+        };
+        let state = self.session.state();
+        let saved = state
+            .validate_die_name(&editor.name, editor.index)
+            .and_then(|name| {
+                let faces = Symbol::parse_faces(&editor.faces)?;
+                // Faces the die keeps stay as named and colored in the gui.
+                match editor.index.and_then(|index| state.get_dice().get(index)) {
+                    Some(die) => Ok((name, die.keep_faces(faces))),
+                    None => Ok((name, faces)),
                 }
             });
+        match saved {
+            Ok((name, faces)) => {
+                self.session.save_die(editor.index, name.clone(), faces);
+                self.editor = None;
+                self.animation = None;
+                self.feedback = Some(trf(Message::DieSaved, &[&name]));
+            }
+            Err(error) => editor.error = Some(error),
         }
     }
 
-    fn next_die(&mut self) {
-        if self.current_range.len() == 0 {
+    fn duplicate_die(&mut self) {
+        let Some(die) = self.session.duplicate_die() else {
             return;
-        }
-        if self.current_index.is_none() && self.current_range.len() != 0 {
-            self.current_index = Some(0);
-        } else {
-            let max_index = self.current_range.end;
-            self.current_index =
-                self.current_index
-                    .map(|index| if index + 1 > max_index { 0 } else { index + 1 });
-        }
+        };
+        self.feedback = Some(trf(Message::DieSaved, &[&die.get_name()]));
+        self.animation = None;
+    }
+
+    fn delete_die(&mut self) {
+        let Some(die) = self.session.remove_die() else {
+            return;
+        };
+        self.feedback = Some(trf(Message::DieDeleted, &[&die.get_name()]));
+        self.animation = None;
     }
 
     fn roll_die(&mut self) {
-        if let Some(index) = self.current_index {
-            if let Some(die) = self.state.get_dice().get(index) {
-                let die_range = die.get_range();
-                self.current_die_roll = Some(self.random.rand_range(die_range));
+        self.settle_animation();
+        if self.session.roll_die().is_none() {
+            return;
+        }
+        let Some((index, _)) = self.session.die_roll() else {
+            return;
+        };
+        let Some(range) = self.session.selected_die().map(|die| die.get_range()) else {
+            return;
+        };
+        self.animation = Some(Animation {
+            die_index: index,
+            face: self.session.random().rand_range(range),
+            ticks_left: (ANIMATION_DURATION.as_millis() / self.tick_rate.as_millis().max(1)).max(1)
+                as u32,
+        });
+    }
+
+    fn roll_hand(&mut self) {
+        if self.session.roll_hand().is_none() {
+            return;
+        }
+        let Some((roll, _)) = self.session.hand_roll() else {
+            return;
+        };
+        let dice = self.session.state().get_dice();
+        for (index, value) in roll.get_rolls() {
+            if let Some(die) = dice.get(*index) {
+                self.history.record(die.get_name(), *value);
             }
         }
+        self.history_selected = 0;
+    }
+
+    /// Rolls the selected die many times on a background thread, streaming the
+    /// results into the history.
+    fn simulate(&mut self) {
+        let Some(die) = self.session.selected_die() else {
+            return;
+        };
+        let name = die.get_name().to_string();
+        let range = die.get_range();
+        let random = self.session.random();
+        let seed = (random.rand_u32() as u64) << 32 | random.rand_u32() as u64;
+        let mut random = Rand32::new(seed);
+        let sender = self.sender();
+        self.feedback = Some(trf(Message::SimulationStarted, &[&name]));
+        thread::spawn(move || {
+            for _ in 0..SIMULATION_BATCHES {
+                let values = (0..SIMULATION_BATCH_SIZE)
+                    .map(|_| random.rand_range(range.clone()))
+                    .collect();
+                let rolls = AppMessage::Rolls {
+                    die: name.clone(),
+                    values,
+                };
+                if sender.send(rolls).is_err() {
+                    return;
+                }
+                thread::sleep(SIMULATION_PAUSE);
+            }
+            let count = SIMULATION_BATCHES * SIMULATION_BATCH_SIZE;
+            let finished = trf(Message::SimulationFinished, &[&count, &name]);
+            let _ = sender.send(AppMessage::Status(finished));
+        });
+    }
+
+    fn copy_history_entry(&mut self) {
+        let entries = self.history.get_entries();
+        let Some(entry) = entries
+            .len()
+            .checked_sub(self.history_selected + 1)
+            .and_then(|index| entries.get(index))
+        else {
+            return;
+        };
+        let text = format!(
+            "{} {}: {}",
+            entry.format_time(),
+            entry.get_die(),
+            entry.get_detail()
+        );
+        let clipboard = match self.clipboard.as_mut() {
+            Some(clipboard) => Ok(clipboard),
+            None => arboard::Clipboard::new().map(|clipboard| self.clipboard.insert(clipboard)),
+        };
+        let copied = clipboard.and_then(|clipboard| clipboard.set_text(&text));
+        self.feedback = Some(match copied {
+            Ok(()) => trf(Message::Copied, &[&text]),
+            Err(error) => trf(Message::CopyFailed, &[&error]),
+        });
     }
 }
 
-impl<'a> Widget for &'a App<'a> {
-    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+impl Widget for &App {
+    fn render(self, area: Rect, buf: &mut Buffer)
     where
         Self: Sized,
     {
-        let title = Title::from(" wuerfel App ".bold());
-        let instructions = if self.current_index.is_some() {
-            Title::from(Line::from(vec![
-                " Previous ".into(),
-                "<Left>".blue().bold(),
-                " Roll die ".into(),
-                "<Enter>".blue().bold(),
-                " Next ".into(),
-                "<Right>".blue().bold(),
-                " Quit ".into(),
-                "<Q> ".blue().bold(),
-            ]))
+        let title = Title::from(format!(" {} ", tr(Message::AppTitle)).bold());
+        let mut instructions = if self.search.is_some() {
+            key_hints(
+                &[
+                    (Message::Select, "<Enter>".to_string()),
+                    (Message::Cancel, "<Esc>".to_string()),
+                ],
+                &self.theme,
+            )
+        } else if self.session.selected().is_some() {
+            self.hints(&[
+                (Message::Previous, &[Action::Previous]),
+                (Message::RollDie, &[Action::Roll]),
+                (Message::Next, &[Action::Next]),
+                (Message::Search, &[Action::Search]),
+                (Message::Quit, &[Action::Quit]),
+            ])
         } else {
-            Title::from(Line::from(vec![
-                " Previous ".into(),
-                "<Left>".blue().bold(),
-                " Next ".into(),
-                "<Right>".blue().bold(),
-                " Quit ".into(),
-                "<Q> ".blue().bold(),
-            ]))
-        };
-        let block = Block::bordered()
+            self.hints(&[
+                (Message::Previous, &[Action::Previous]),
+                (Message::Next, &[Action::Next]),
+                (Message::Search, &[Action::Search]),
+                (Message::Quit, &[Action::Quit]),
+            ])
+        };
+        instructions.push_span(" ");
+        let block = self
+            .theme
+            .block()
             .title(title.alignment(Alignment::Center))
             .title(
-                instructions
+                Title::from(instructions)
                     .alignment(Alignment::Center)
                     .position(Position::Bottom),
-            )
-            .border_set(border::THICK);
+            );
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let statistics = self.history.statistics();
+        let footer_height = statistics.len().clamp(1, MAX_STATISTICS_ROWS) as u16 + 3;
+        let [body, footer] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(footer_height)]).areas(inner);
+        // Narrow terminals only show the selected die, without dice list and history.
+        let main = if body.width >= MIN_SIDE_PANES_WIDTH {
+            let [dice_list, main, history] = Layout::horizontal([
+                Constraint::Length(self.dice_list_width()),
+                Constraint::Min(0),
+                Constraint::Percentage(40),
+            ])
+            .areas(body);
+            self.render_dice_list(dice_list, buf);
+            self.render_history(history, buf);
+            main
+        } else {
+            self.dice_list_area.set(Rect::default());
+            self.history_area.set(Rect::default());
+            body
+        };
+        match self.session.selected_die() {
+            Some(die) => {
+                let [dice, histogram] =
+                    Layout::vertical([Constraint::Min(0), Constraint::Percentage(50)]).areas(main);
+                self.render_dice(dice, buf);
+                self.render_histogram(die, histogram, buf);
+            }
+            None => self.render_dice(main, buf),
+        }
+        render_statistics(&statistics, footer, buf, &self.theme);
+        if let Some(editor) = &self.editor {
+            editor.render(inner, buf, &self.theme);
+        }
+    }
+}
+
+impl App {
+    /// Key hints with the keys currently bound to each group of actions.
+    fn hints(&self, entries: &[(Message, &[Action])]) -> Line<'static> {
+        let entries = entries
+            .iter()
+            .filter_map(|(message, actions)| Some((*message, self.key_bindings.hint(actions)?)))
+            .collect::<Vec<_>>();
+        key_hints(&entries, &self.theme)
+    }
+
+    /// Wide enough for the longest die name with its shortcut and for the key hint.
+    fn dice_list_width(&self) -> u16 {
+        let hint = format!(" {} <1-9> ", tr(Message::Select)).chars().count();
+        let longest = self
+            .session
+            .state()
+            .get_dice()
+            .iter()
+            .map(|die| die.get_name().chars().count() + 4)
+            .chain([hint])
+            .max()
+            .unwrap_or_default();
+        (longest + 2) as u16
+    }
+
+    /// Every die with its number key, the selected one highlighted.
+    fn render_dice_list(&self, area: Rect, buf: &mut Buffer) {
+        self.dice_list_area.set(area);
+        let block = self
+            .theme
+            .block()
+            .title(format!(" {} ", tr(Message::Dice)))
+            .title(
+                Title::from(key_hints(
+                    &[(Message::Select, "<1-9>".to_string())],
+                    &self.theme,
+                ))
+                .position(Position::Bottom),
+            );
+        let items = self
+            .session
+            .state()
+            .get_dice()
+            .iter()
+            .enumerate()
+            .map(|(index, die)| {
+                let shortcut = if index < 9 {
+                    (index + 1).to_string()
+                } else {
+                    " ".to_string()
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(shortcut, self.theme.keys),
+                    " ".into(),
+                    die.get_name().to_string().into(),
+                ]))
+            })
+            .collect::<Vec<_>>();
+        if items.is_empty() {
+            Paragraph::new(tr(Message::None).italic())
+                .block(block)
+                .render(area, buf);
+            return;
+        }
+        let mut list_state = ListState::default()
+            .with_offset(self.dice_list_offset.get())
+            .with_selected(self.session.selected());
+        StatefulWidget::render(
+            List::new(items)
+                .block(block)
+                .highlight_style(self.theme.highlight),
+            area,
+            buf,
+            &mut list_state,
+        );
+        self.dice_list_offset.set(list_state.offset());
+    }
 
-        let mut dice_text = Text::from(vec![Line::from(vec![
-            "Dice: ".into(),
-            self.state.print_dice().unwrap_or("None".into()).yellow(),
-        ])]);
-        if let Some(index) = self.current_index {
-            if let Some(die) = self.state.get_dice().get(index) {
-                dice_text.push_line(Line::from(vec![
-                    "Currently selected die: ".into(),
-                    die.get_name().into(),
-                ]));
+    fn render_dice(&self, area: Rect, buf: &mut Buffer) {
+        let state = self.session.state();
+        let roll_options = self.session.roll_options();
+        let mut dice_text = Text::default();
+        if let Some(die) = self.session.selected_die() {
+            dice_text.push_line(Line::from(vec![
+                format!("{}: ", tr(Message::SelectedDie)).into(),
+                die.get_name().into(),
+            ]));
+        }
+        if *roll_options != RollOptions::default() {
+            let mut options = vec![
+                format!("{}: ", tr(Message::Modifier)).into(),
+                Span::styled(format!("{:+}", roll_options.modifier), self.theme.accent),
+            ];
+            if let Some(target) = roll_options.target {
+                options.push(format!("  {}: ", tr(Message::Target)).into());
+                options.push(Span::styled(target.to_string(), self.theme.accent));
+            }
+            if roll_options.mode != RollMode::Normal {
+                options.push(format!("  {}: ", tr(Message::RollMode)).into());
+                options.push(Span::styled(roll_options.mode.label(), self.theme.accent));
+            }
+            dice_text.push_line(Line::from(options));
+        }
+        if let Some(animation) = &self.animation {
+            if let Some(die) = state.get_dice().get(animation.die_index) {
+                for line in face::die_face(die.get_range().len(), animation.face) {
+                    dice_text.push_line(Line::styled(line, self.theme.muted));
+                }
+            }
+        } else if let Some((index, roll)) = &self.session.die_roll() {
+            if let Some(die) = state.get_dice().get(*index) {
+                for line in face::die_face(die.get_range().len(), roll.natural()) {
+                    dice_text.push_line(Line::styled(line, self.theme.accent.bold()));
+                }
             }
+            dice_text.push_line(Line::from(vec![
+                format!("{}: ", tr(Message::CurrentRoll)).into(),
+                styled_result(roll, &self.theme),
+            ]));
         }
-        if let Some(roll) = self.current_die_roll {
+        if let Some(hand) = state.describe_hand(state.get_hand()) {
             dice_text.push_line(Line::from(vec![
-                "Current roll: ".into(),
-                roll.to_string().into(),
+                format!("{}: ", tr(Message::Hand)).into(),
+                Span::styled(hand, self.theme.accent),
             ]));
         }
+        if let Some((roll, result)) = self.session.hand_roll() {
+            dice_text.push_line(Line::from(vec![
+                format!("{}: ", tr(Message::HandRoll)).into(),
+                state.describe_hand_roll(roll).into(),
+            ]));
+            if *roll_options != RollOptions::default() {
+                dice_text.push_line(Line::from(styled_result(result, &self.theme)));
+            }
+        }
+        if self.search.is_none() {
+            dice_text.push_line(self.hints(&[
+                (Message::AddToHand, &[Action::AddToHand]),
+                (Message::RemoveFromHand, &[Action::RemoveFromHand]),
+                (Message::RollHand, &[Action::RollHand]),
+                (Message::ClearHand, &[Action::ClearHand]),
+            ]));
+            dice_text.push_line(self.hints(&[
+                (
+                    Message::Modifier,
+                    &[Action::IncreaseModifier, Action::DecreaseModifier],
+                ),
+                (
+                    Message::Target,
+                    &[
+                        Action::ToggleTarget,
+                        Action::DecreaseTarget,
+                        Action::IncreaseTarget,
+                    ],
+                ),
+                (Message::RollMode, &[Action::CycleMode]),
+                (Message::Simulate, &[Action::Simulate]),
+            ]));
+            dice_text.push_line(self.hints(&[
+                (Message::NewDie, &[Action::NewDie]),
+                (Message::EditDie, &[Action::EditDie]),
+                (Message::DuplicateDie, &[Action::DuplicateDie]),
+                (Message::DeleteDie, &[Action::DeleteDie]),
+            ]));
+        }
+        if let Some(search) = &self.search {
+            dice_text.push_line(Line::from(vec![
+                format!("{}: ", tr(Message::Search)).into(),
+                Span::styled(search.clone(), self.theme.accent),
+                "_".into(),
+            ]));
+        }
+        if let Some(feedback) = &self.feedback {
+            dice_text.push_line(Line::from(feedback.clone().italic()));
+        }
         Paragraph::new(dice_text)
             .centered()
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+
+    /// Bars with how often each face of `die` was rolled, against the expected uniform count.
+    fn render_histogram(&self, die: &Die, area: Rect, buf: &mut Buffer) {
+        let faces = die.get_range();
+        let counts = self.history.face_counts(die.get_name(), faces.clone());
+        let total = counts.iter().sum::<u64>();
+        let expected = total as f64 / counts.len().max(1) as f64;
+        // A bar of height zero would still be drawn as a half block.
+        let bars = faces
+            .clone()
+            .zip(counts.iter())
+            .filter(|(_, count)| **count > 0)
+            .map(|(face, count)| (face as f64, *count as f64))
+            .collect::<Vec<_>>();
+        let first = faces.start as f64;
+        let last = faces.end.saturating_sub(1) as f64;
+        let uniform = [(first - 0.5, expected), (last + 0.5, expected)];
+        let top = counts
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or_default()
+            .max(expected.ceil() as u64)
+            .max(1);
+        // Rounded up to an even count, so the middle tick is a whole number as well.
+        let ticks = match top {
+            1 => vec![0, 1],
+            _ => {
+                let top = top.next_multiple_of(2);
+                vec![0, top / 2, top]
+            }
+        };
+        let top = ticks.last().copied().unwrap_or(1) as f64;
+
+        let datasets = vec![
+            Dataset::default()
+                .name(tr(Message::Rolled))
+                .graph_type(GraphType::Bar)
+                .marker(symbols::Marker::HalfBlock)
+                .style(self.theme.accent)
+                .data(&bars),
+            Dataset::default()
+                .name(tr(Message::Expected))
+                .graph_type(GraphType::Line)
+                .marker(symbols::Marker::Braille)
+                .style(self.theme.expected)
+                .data(&uniform),
+        ];
+        let x_labels =
+            [first, (first + last) / 2.0, last].map(|face| Span::from(format!("{:.0}", face)));
+        let y_labels = ticks
+            .iter()
+            .map(|count| Span::from(count.to_string()))
+            .collect::<Vec<_>>();
+        Chart::new(datasets)
+            .block(
+                self.theme
+                    .block()
+                    .title(format!(" {} ", trf(Message::Histogram, &[&die.get_name()]))),
+            )
+            .x_axis(
+                Axis::default()
+                    .bounds([first - 0.5, last + 0.5])
+                    .labels(x_labels),
+            )
+            .y_axis(Axis::default().bounds([0.0, top]).labels(y_labels))
+            .legend_position(Some(LegendPosition::TopRight))
+            .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)))
+            .render(area, buf);
+    }
+
+    fn render_history(&self, area: Rect, buf: &mut Buffer) {
+        let block = self
+            .theme
+            .block()
+            .title(format!(" {} ", tr(Message::History)))
+            .title(
+                Title::from(self.hints(&[
+                    (Message::Scroll, &[Action::HistoryUp, Action::HistoryDown]),
+                    (Message::CopyEntry, &[Action::CopyEntry]),
+                    (Message::ClearHistory, &[Action::ClearHistory]),
+                ]))
+                .position(Position::Bottom),
+            );
+        let items = self
+            .history
+            .get_entries()
+            .iter()
+            .rev()
+            .map(|entry| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(entry.format_time(), self.theme.muted),
+                    " ".into(),
+                    Span::styled(entry.get_die().to_string(), self.theme.accent),
+                    " ".into(),
+                    entry.get_detail().to_string().into(),
+                ]))
+            })
+            .collect::<Vec<_>>();
+        let mut list_state = ListState::default()
+            .with_selected((!items.is_empty()).then_some(self.history_selected));
+        self.history_area.set(area);
+        StatefulWidget::render(
+            List::new(items)
+                .block(block)
+                .highlight_style(self.theme.highlight),
+            area,
+            buf,
+            &mut list_state,
+        );
+    }
+}
+
+/// Table of count, mean and extremes per die, limited to the most recently added dice.
+fn render_statistics(statistics: &[DieStatistics], area: Rect, buf: &mut Buffer, theme: &Theme) {
+    let block = theme
+        .block()
+        .title(format!(" {} ", tr(Message::Statistics)));
+    if statistics.is_empty() {
+        Paragraph::new(tr(Message::NoRolls).italic())
             .block(block)
             .render(area, buf);
+        return;
     }
+    let header = Row::new([
+        tr(Message::DieHeader),
+        tr(Message::Count),
+        tr(Message::Mean),
+        tr(Message::Min),
+        tr(Message::Max),
+    ])
+    .bold();
+    let skipped = statistics.len().saturating_sub(MAX_STATISTICS_ROWS);
+    let rows = statistics.iter().skip(skipped).map(|stats| {
+        Row::new([
+            stats.die.clone(),
+            stats.count.to_string(),
+            format!("{:.2}", stats.mean),
+            stats.min.to_string(),
+            stats.max.to_string(),
+        ])
+    });
+    Widget::render(
+        Table::new(rows, [Constraint::Fill(1); 5])
+            .header(header)
+            .block(block),
+        area,
+        buf,
+    );
 }
 
-pub fn run_tui<'a>(state: State<'a>, random: Rand32) -> Result<(), Error> {
-    let range = 0..(state.get_dice().len() - 1);
-    let mut app = App {
-        state,
-        random,
-        current_index: None,
-        current_range: range,
-        current_die_roll: None,
-        exit: false,
-    };
+/// Colors a roll by its verdict, or by a natural maximum or minimum without a target.
+fn styled_result(result: &RollResult, theme: &Theme) -> Span<'static> {
+    let text = result.to_string();
+    match result.outcome() {
+        Outcome::Success => Span::styled(text, theme.success),
+        Outcome::Failure => Span::styled(text, theme.failure),
+        Outcome::Neutral => text.into(),
+    }
+}
+
+/// Pairs of action label and key hint, the keys styled by the theme.
+fn key_hints(entries: &[(Message, String)], theme: &Theme) -> Line<'static> {
+    let mut spans = vec![];
+    for (message, key) in entries {
+        spans.push(format!(" {} ", tr(*message)).into());
+        spans.push(Span::styled(key.clone(), theme.keys));
+    }
+    Line::from(spans)
+}
+
+pub fn run_tui(
+    state: State,
+    selected_die: Option<usize>,
+    roll_mode: RollMode,
+    tick_rate: Duration,
+    settings: Settings,
+    random: Rand32,
+) -> Result<(), Error> {
+    let mut app = App::new(state, selected_die, roll_mode, tick_rate, settings, random);
     let mut terminal = ratatui::init();
-    terminal.clear()?;
-    let app_result = app.run(&mut terminal);
+    let app_result = execute!(io::stdout(), EnableMouseCapture)
+        .and_then(|()| terminal.clear())
+        .map_err(Error::from)
+        .and_then(|()| app.run(&mut terminal));
+    // Restored on every path, so that an error does not leave the terminal in raw mode.
+    let released = execute!(io::stdout(), DisableMouseCapture);
     ratatui::restore();
-    app_result
+    app_result?;
+    Ok(released?)
 }
@@ -0,0 +1,258 @@
+use ratatui::crossterm::event::KeyCode;
+
+use crate::{
+    config,
+    locale::{trf, Message},
+};
+
+/// Everything the main screen does on a key press, named as in the `[tui.keys]` section.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Action {
+    Quit,
+    Previous,
+    Next,
+    Roll,
+    AddToHand,
+    RemoveFromHand,
+    RollHand,
+    ClearHand,
+    IncreaseModifier,
+    DecreaseModifier,
+    CycleMode,
+    ToggleTarget,
+    IncreaseTarget,
+    DecreaseTarget,
+    HistoryUp,
+    HistoryDown,
+    ClearHistory,
+    CopyEntry,
+    Simulate,
+    Search,
+    NewDie,
+    EditDie,
+    DuplicateDie,
+    DeleteDie,
+}
+
+impl Action {
+    const ALL: [Action; 24] = [
+        Action::Quit,
+        Action::Previous,
+        Action::Next,
+        Action::Roll,
+        Action::AddToHand,
+        Action::RemoveFromHand,
+        Action::RollHand,
+        Action::ClearHand,
+        Action::IncreaseModifier,
+        Action::DecreaseModifier,
+        Action::CycleMode,
+        Action::ToggleTarget,
+        Action::IncreaseTarget,
+        Action::DecreaseTarget,
+        Action::HistoryUp,
+        Action::HistoryDown,
+        Action::ClearHistory,
+        Action::CopyEntry,
+        Action::Simulate,
+        Action::Search,
+        Action::NewDie,
+        Action::EditDie,
+        Action::DuplicateDie,
+        Action::DeleteDie,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Previous => "previous",
+            Action::Next => "next",
+            Action::Roll => "roll",
+            Action::AddToHand => "add_to_hand",
+            Action::RemoveFromHand => "remove_from_hand",
+            Action::RollHand => "roll_hand",
+            Action::ClearHand => "clear_hand",
+            Action::IncreaseModifier => "increase_modifier",
+            Action::DecreaseModifier => "decrease_modifier",
+            Action::CycleMode => "cycle_mode",
+            Action::ToggleTarget => "toggle_target",
+            Action::IncreaseTarget => "increase_target",
+            Action::DecreaseTarget => "decrease_target",
+            Action::HistoryUp => "history_up",
+            Action::HistoryDown => "history_down",
+            Action::ClearHistory => "clear_history",
+            Action::CopyEntry => "copy_entry",
+            Action::Simulate => "simulate",
+            Action::Search => "search",
+            Action::NewDie => "new_die",
+            Action::EditDie => "edit_die",
+            Action::DuplicateDie => "duplicate_die",
+            Action::DeleteDie => "delete_die",
+        }
+    }
+}
+
+/// Keys of every action, looked up in order so the first binding of a key wins.
+pub(super) struct KeyBindings {
+    bindings: Vec<(KeyCode, Action)>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        use Action::*;
+        let bindings = [
+            (KeyCode::Char('q'), Quit),
+            (KeyCode::Char('Q'), Quit),
+            (KeyCode::Esc, Quit),
+            (KeyCode::Left, Previous),
+            (KeyCode::Right, Next),
+            (KeyCode::Enter, Roll),
+            (KeyCode::Char('a'), AddToHand),
+            (KeyCode::Char('d'), RemoveFromHand),
+            (KeyCode::Char('r'), RollHand),
+            (KeyCode::Char('x'), ClearHand),
+            (KeyCode::Char('+'), IncreaseModifier),
+            (KeyCode::Char('-'), DecreaseModifier),
+            (KeyCode::Char('m'), CycleMode),
+            (KeyCode::Char('t'), ToggleTarget),
+            (KeyCode::Char('>'), IncreaseTarget),
+            (KeyCode::Char('<'), DecreaseTarget),
+            (KeyCode::Up, HistoryUp),
+            (KeyCode::Down, HistoryDown),
+            (KeyCode::Char('c'), ClearHistory),
+            (KeyCode::Char('y'), CopyEntry),
+            (KeyCode::Char('s'), Simulate),
+            (KeyCode::Char('/'), Search),
+            (KeyCode::Char('n'), NewDie),
+            (KeyCode::Char('e'), EditDie),
+            (KeyCode::Char('u'), DuplicateDie),
+            (KeyCode::Delete, DeleteDie),
+        ];
+        Self {
+            bindings: bindings.to_vec(),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Defaults extended by a vim-like `preset` and the per-action keys of the
+    /// `[tui.keys]` section, e.g. `roll = ["Space", "Enter"]`.
+    pub(super) fn from_config(section: Option<&toml::Table>) -> Result<KeyBindings, String> {
+        let mut bindings = KeyBindings::default();
+        let Some(section) = section else {
+            return Ok(bindings);
+        };
+        // The preset goes first, so the keys of single actions can override it.
+        if let Some(preset) = section.get("preset") {
+            match preset.as_str() {
+                Some("default") => {}
+                Some("vim") => bindings.add_vim_keys(),
+                _ => {
+                    return Err(trf(
+                        Message::UnknownPreset,
+                        &[&config::describe(preset), &"tui.keys"],
+                    ))
+                }
+            }
+        }
+        for (name, value) in section.iter().filter(|(name, _)| *name != "preset") {
+            let action = Action::ALL
+                .into_iter()
+                .find(|action| action.name() == name)
+                .ok_or_else(|| trf(Message::UnknownAction, &[name]))?;
+            let keys = match value {
+                toml::Value::String(key) => vec![parse_key(key)?],
+                toml::Value::Array(keys) => keys
+                    .iter()
+                    .map(|key| parse_key(key.as_str().unwrap_or_default()))
+                    .collect::<Result<_, _>>()?,
+                _ => return Err(trf(Message::UnknownKey, &[&config::describe(value)])),
+            };
+            bindings.bind(action, keys);
+        }
+        Ok(bindings)
+    }
+
+    /// h and l move through the dice, Space rolls, k and j scroll the history.
+    fn add_vim_keys(&mut self) {
+        for (key, action) in [
+            (KeyCode::Char('h'), Action::Previous),
+            (KeyCode::Char('l'), Action::Next),
+            (KeyCode::Char(' '), Action::Roll),
+            (KeyCode::Char('k'), Action::HistoryUp),
+            (KeyCode::Char('j'), Action::HistoryDown),
+        ] {
+            self.bindings.retain(|(bound, _)| *bound != key);
+            self.bindings.push((key, action));
+        }
+    }
+
+    /// Replaces the keys of `action`, taking them away from other actions.
+    fn bind(&mut self, action: Action, keys: Vec<KeyCode>) {
+        self.bindings
+            .retain(|(key, bound)| *bound != action && !keys.contains(key));
+        self.bindings
+            .extend(keys.into_iter().map(|key| (key, action)));
+    }
+
+    pub(super) fn action(&self, key: KeyCode) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == key)
+            .map(|(_, action)| *action)
+    }
+
+    /// Key hint like `<Left>` or `<+/->` with the first key of each action, or
+    /// `None` if none of the actions is bound.
+    pub(super) fn hint(&self, actions: &[Action]) -> Option<String> {
+        let keys = actions
+            .iter()
+            .filter_map(|action| {
+                self.bindings
+                    .iter()
+                    .find(|(_, bound)| bound == action)
+                    .map(|(key, _)| key_name(*key))
+            })
+            .collect::<Vec<_>>();
+        (!keys.is_empty()).then(|| format!("<{}>", keys.join("/")))
+    }
+}
+
+fn parse_key(name: &str) -> Result<KeyCode, String> {
+    let key = match name.to_lowercase().as_str() {
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "space" => KeyCode::Char(' '),
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" | "ins" => KeyCode::Insert,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => return Err(trf(Message::UnknownKey, &[&name])),
+            }
+        }
+    };
+    Ok(key)
+}
+
+fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_uppercase().to_string(),
+        KeyCode::Delete => "Del".to_string(),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        key => format!("{:?}", key),
+    }
+}
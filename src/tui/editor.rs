@@ -0,0 +1,153 @@
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::KeyCode,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span, Text},
+    widgets::{
+        block::{Position, Title},
+        Clear, Paragraph, Widget, Wrap,
+    },
+};
+
+use crate::{
+    locale::{tr, Message},
+    Die,
+};
+
+use super::{key_hints, theme::Theme};
+
+const POPUP_WIDTH: u16 = 56;
+const POPUP_HEIGHT: u16 = 8;
+
+/// Popup form to create a die or change the name and faces of an existing one.
+pub(super) struct Editor {
+    /// Die being edited, or `None` for a new die.
+    pub(super) index: Option<usize>,
+    pub(super) name: String,
+    /// Faces as typed, parsed with [`crate::Symbol::parse_faces`] on save.
+    pub(super) faces: String,
+    field: Field,
+    /// Why the last attempt to save was rejected.
+    pub(super) error: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Name,
+    Faces,
+}
+
+/// What the app should do after the editor handled a key.
+pub(super) enum EditorAction {
+    Continue,
+    Save,
+    Cancel,
+}
+
+impl Editor {
+    pub(super) fn new_die() -> Self {
+        Self {
+            index: None,
+            name: String::new(),
+            faces: "1-6".to_string(),
+            field: Field::Name,
+            error: None,
+        }
+    }
+
+    pub(super) fn edit(index: usize, die: &Die) -> Self {
+        Self {
+            index: Some(index),
+            name: die.get_name().to_string(),
+            faces: die.describe_faces(),
+            field: Field::Name,
+            error: None,
+        }
+    }
+
+    pub(super) fn handle_key(&mut self, code: KeyCode) -> EditorAction {
+        match code {
+            KeyCode::Esc => return EditorAction::Cancel,
+            KeyCode::Enter => return EditorAction::Save,
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down => {
+                self.field = match self.field {
+                    Field::Name => Field::Faces,
+                    Field::Faces => Field::Name,
+                }
+            }
+            KeyCode::Backspace => {
+                self.input().pop();
+            }
+            KeyCode::Char(c) => self.input().push(c),
+            _ => {}
+        }
+        EditorAction::Continue
+    }
+
+    fn input(&mut self) -> &mut String {
+        match self.field {
+            Field::Name => &mut self.name,
+            Field::Faces => &mut self.faces,
+        }
+    }
+
+    /// Draws the form centered on `area`, clearing what is underneath.
+    pub(super) fn render(&self, area: Rect, buf: &mut Buffer, theme: &Theme) {
+        let [popup] = Layout::horizontal([Constraint::Length(POPUP_WIDTH)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup] = Layout::vertical([Constraint::Length(POPUP_HEIGHT)])
+            .flex(Flex::Center)
+            .areas(popup);
+        let title = match self.index {
+            Some(_) => tr(Message::EditDie),
+            None => tr(Message::NewDie),
+        };
+        let block = theme
+            .block()
+            .title(Title::from(format!(" {} ", title).bold()).alignment(Alignment::Center))
+            .title(
+                Title::from(key_hints(
+                    &[
+                        (Message::Save, "<Enter>".to_string()),
+                        (Message::NextField, "<Tab>".to_string()),
+                        (Message::Cancel, "<Esc>".to_string()),
+                    ],
+                    theme,
+                ))
+                .alignment(Alignment::Center)
+                .position(Position::Bottom),
+            );
+        let mut text = Text::from(self.field_line(Field::Name, Message::Name, &self.name, theme));
+        text.push_line(self.field_line(Field::Faces, Message::Faces, &self.faces, theme));
+        text.push_line(Line::styled(tr(Message::FacesHint), theme.muted.italic()));
+        if let Some(error) = &self.error {
+            text.push_line(Line::styled(error.clone(), theme.failure));
+        }
+        Clear.render(popup, buf);
+        Paragraph::new(text)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .render(popup, buf);
+    }
+
+    fn field_line(
+        &self,
+        field: Field,
+        label: Message,
+        value: &str,
+        theme: &Theme,
+    ) -> Line<'static> {
+        let label = format!("{}: ", tr(label));
+        if self.field == field {
+            Line::from(vec![
+                label.bold(),
+                Span::styled(value.to_string(), theme.accent),
+                "_".into(),
+            ])
+        } else {
+            Line::from(vec![label.into(), value.to_string().into()])
+        }
+    }
+}
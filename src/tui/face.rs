@@ -0,0 +1,93 @@
+const D4: [&str; 5] = [
+    "      ╱╲      ",
+    "     ╱  ╲     ",
+    "    ╱    ╲    ",
+    "   ╱ {}  ╲   ",
+    "  ╱────────╲  ",
+];
+
+const D10: [&str; 6] = [
+    "      ╱╲      ",
+    "    ╱    ╲    ",
+    "  ╱  {}   ╲  ",
+    "  ╲        ╱  ",
+    "    ╲    ╱    ",
+    "      ╲╱      ",
+];
+
+const D20: [&str; 5] = [
+    "   ╱──────╲   ",
+    "  ╱        ╲  ",
+    " │   {}    │ ",
+    "  ╲        ╱  ",
+    "   ╲──────╱   ",
+];
+
+const BOX: [&str; 5] = [
+    " ┌──────────┐ ",
+    " │          │ ",
+    " │   {}    │ ",
+    " │          │ ",
+    " └──────────┘ ",
+];
+
+const PIP: char = '●';
+
+/// Lines of a face showing `value` for a die with `faces` sides.
+///
+/// Six-sided dice show pips, four-, ten- and twenty-sided dice a numbered outline of
+/// their face and every other die a numbered box.
+pub(super) fn die_face(faces: usize, value: u32) -> Vec<String> {
+    match faces {
+        6 if (1..=6).contains(&value) => pips(value),
+        4 => numbered(&D4, value),
+        10 => numbered(&D10, value),
+        20 => numbered(&D20, value),
+        _ => numbered(&BOX, value),
+    }
+}
+
+fn numbered(template: &[&str], value: u32) -> Vec<String> {
+    let numeral = format!("{:^3}", value);
+    template
+        .iter()
+        .map(|line| line.replace("{}", &numeral))
+        .collect()
+}
+
+/// A D6 face with the pips laid out on a 3×3 grid.
+fn pips(value: u32) -> Vec<String> {
+    // Rows of the grid as (left, center, right).
+    let grid: [[bool; 3]; 3] = match value {
+        1 => [[false; 3], [false, true, false], [false; 3]],
+        2 => [[true, false, false], [false; 3], [false, false, true]],
+        3 => [
+            [true, false, false],
+            [false, true, false],
+            [false, false, true],
+        ],
+        4 => [[true, false, true], [false; 3], [true, false, true]],
+        5 => [
+            [true, false, true],
+            [false, true, false],
+            [true, false, true],
+        ],
+        _ => [
+            [true, false, true],
+            [true, false, true],
+            [true, false, true],
+        ],
+    };
+    let pip = |set: bool| if set { PIP } else { ' ' };
+    let mut lines = vec![" ┌───────────┐ ".to_string()];
+    for [left, center, right] in grid {
+        lines.push(format!(
+            " │ {}   {}   {} │ ",
+            pip(left),
+            pip(center),
+            pip(right)
+        ));
+    }
+    lines.push(" └───────────┘ ".to_string());
+    lines
+}
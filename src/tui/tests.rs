@@ -0,0 +1,135 @@
+use insta::assert_snapshot;
+use ratatui::{backend::TestBackend, crossterm::event::KeyModifiers, Terminal};
+
+use super::*;
+
+const WIDTH: u16 = 110;
+const HEIGHT: u16 = 32;
+
+fn default_state() -> State {
+    let mut state = State::default();
+    crate::setup_default_dice(&mut state);
+    state
+}
+
+/// An app with a fixed seed, so rolls and tumbling faces are the same on every run.
+fn app(state: State, selected_die: Option<usize>) -> App {
+    App::new(
+        state,
+        selected_die,
+        RollMode::Normal,
+        DEFAULT_TICK_RATE,
+        Settings::default(),
+        Rand32::new(7),
+    )
+}
+
+fn draw(app: &App, width: u16, height: u16) -> Terminal<TestBackend> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|frame| app.draw(frame)).unwrap();
+    terminal
+}
+
+fn press(app: &mut App, code: KeyCode) {
+    app.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+}
+
+/// Ticks until the tumbling die shows its result.
+fn settle(app: &mut App) {
+    while app.animation.is_some() {
+        app.on_tick();
+    }
+}
+
+#[test]
+fn empty_state() {
+    let app = app(State::default(), None);
+    assert_snapshot!(draw(&app, WIDTH, HEIGHT).backend());
+}
+
+#[test]
+fn empty_state_ignores_navigation() {
+    let mut app = app(State::default(), None);
+    press(&mut app, KeyCode::Right);
+    press(&mut app, KeyCode::Left);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.session.selected(), None);
+    assert!(app.animation.is_none());
+}
+
+#[test]
+fn no_die_selected() {
+    let app = app(default_state(), None);
+    assert_snapshot!(draw(&app, WIDTH, HEIGHT).backend());
+}
+
+#[test]
+fn selected_die() {
+    let app = app(default_state(), Some(1));
+    assert_snapshot!(draw(&app, WIDTH, HEIGHT).backend());
+}
+
+#[test]
+fn tumbling_die() {
+    let mut app = app(default_state(), Some(3));
+    press(&mut app, KeyCode::Enter);
+    app.on_tick();
+    assert_snapshot!(draw(&app, WIDTH, HEIGHT).backend());
+}
+
+#[test]
+fn rolled_value() {
+    let mut app = app(default_state(), Some(1));
+    press(&mut app, KeyCode::Enter);
+    settle(&mut app);
+    assert_eq!(app.history.get_entries().len(), 1);
+    assert_snapshot!(draw(&app, WIDTH, HEIGHT).backend());
+}
+
+#[test]
+fn rolled_value_with_modifier_and_target() {
+    let mut app = app(default_state(), Some(3));
+    press(&mut app, KeyCode::Char('+'));
+    press(&mut app, KeyCode::Char('+'));
+    press(&mut app, KeyCode::Char('t'));
+    press(&mut app, KeyCode::Enter);
+    settle(&mut app);
+    assert_snapshot!(draw(&app, WIDTH, HEIGHT).backend());
+}
+
+#[test]
+fn dice_editor() {
+    let mut app = app(default_state(), Some(0));
+    press(&mut app, KeyCode::Char('e'));
+    press(&mut app, KeyCode::Tab);
+    press(&mut app, KeyCode::Char('9'));
+    press(&mut app, KeyCode::Enter);
+    assert_snapshot!(draw(&app, WIDTH, HEIGHT).backend());
+}
+
+#[test]
+fn narrow_terminal() {
+    let mut app = app(default_state(), Some(1));
+    press(&mut app, KeyCode::Enter);
+    settle(&mut app);
+    assert_snapshot!(draw(&app, 48, 24).backend());
+}
+
+#[test]
+fn tiny_terminal() {
+    let mut app = app(default_state(), Some(2));
+    press(&mut app, KeyCode::Enter);
+    settle(&mut app);
+    assert_snapshot!(draw(&app, 16, 6).backend());
+}
+
+#[test]
+fn advantage_counts_both_faces() {
+    let mut app = app(default_state(), Some(3));
+    press(&mut app, KeyCode::Char('m'));
+    press(&mut app, KeyCode::Enter);
+    settle(&mut app);
+    let counts = app.history.face_counts("D20", 1..21);
+    assert_eq!(app.history.get_entries().len(), 1);
+    assert_eq!(counts.iter().sum::<u64>(), 2);
+}
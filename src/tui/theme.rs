@@ -0,0 +1,149 @@
+use std::str::FromStr;
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    symbols::border,
+    widgets::Block,
+};
+
+use crate::{
+    config,
+    locale::{trf, Message},
+};
+
+/// Styles of the TUI, chosen with `preset` in the `[tui.theme]` section and adjusted
+/// per color, e.g. `accent = "magenta"` or `border = "rounded"`.
+#[derive(Clone, Copy)]
+pub(super) struct Theme {
+    /// Values like the selected die, the modifier and settled faces.
+    pub(super) accent: Style,
+    /// Key hints.
+    pub(super) keys: Style,
+    pub(super) success: Style,
+    pub(super) failure: Style,
+    /// Tumbling faces, timestamps and other secondary text.
+    pub(super) muted: Style,
+    /// Expected counts in the histogram.
+    pub(super) expected: Style,
+    /// Selected entries of the dice list and history.
+    pub(super) highlight: Style,
+    pub(super) border: border::Set,
+}
+
+impl Theme {
+    /// A bordered block drawn with the border set of the theme.
+    pub(super) fn block(&self) -> Block<'static> {
+        Block::bordered().border_set(self.border)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            accent: Style::new().fg(Color::Yellow),
+            keys: Style::new().fg(Color::Blue).add_modifier(Modifier::BOLD),
+            success: Style::new().fg(Color::Green).add_modifier(Modifier::BOLD),
+            failure: Style::new().fg(Color::Red).add_modifier(Modifier::BOLD),
+            muted: Style::new().fg(Color::DarkGray),
+            expected: Style::new().fg(Color::Cyan),
+            highlight: Style::new().add_modifier(Modifier::REVERSED),
+            border: border::THICK,
+        }
+    }
+}
+
+impl Theme {
+    /// Bright colors on bold text for low-contrast terminals or screens.
+    fn high_contrast() -> Self {
+        let bold = |color| Style::new().fg(color).add_modifier(Modifier::BOLD);
+        Self {
+            accent: bold(Color::LightYellow),
+            keys: bold(Color::LightCyan).add_modifier(Modifier::UNDERLINED),
+            success: bold(Color::LightGreen),
+            failure: bold(Color::LightRed),
+            muted: Style::new().fg(Color::White),
+            expected: bold(Color::LightMagenta),
+            highlight: Style::new()
+                .fg(Color::Black)
+                .bg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            border: border::DOUBLE,
+        }
+    }
+
+    /// Only text attributes, for terminals without colors.
+    fn monochrome() -> Self {
+        Self {
+            accent: Style::new().add_modifier(Modifier::BOLD),
+            keys: Style::new().add_modifier(Modifier::UNDERLINED),
+            success: Style::new().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            failure: Style::new().add_modifier(Modifier::BOLD | Modifier::CROSSED_OUT),
+            muted: Style::new().add_modifier(Modifier::DIM),
+            expected: Style::new(),
+            highlight: Style::new().add_modifier(Modifier::REVERSED),
+            border: border::PLAIN,
+        }
+    }
+
+    pub(super) fn from_config(section: Option<&toml::Table>) -> Result<Theme, String> {
+        let Some(section) = section else {
+            return Ok(Theme::default());
+        };
+        let mut theme = match section.get("preset") {
+            None => Theme::default(),
+            Some(preset) => match preset.as_str() {
+                Some("default") => Theme::default(),
+                Some("high-contrast") => Theme::high_contrast(),
+                Some("monochrome") => Theme::monochrome(),
+                _ => {
+                    return Err(trf(
+                        Message::UnknownPreset,
+                        &[&config::describe(preset), &"tui.theme"],
+                    ))
+                }
+            },
+        };
+        for (name, style) in [
+            ("accent", &mut theme.accent),
+            ("keys", &mut theme.keys),
+            ("success", &mut theme.success),
+            ("failure", &mut theme.failure),
+            ("muted", &mut theme.muted),
+            ("expected", &mut theme.expected),
+        ] {
+            if let Some(value) = section.get(name) {
+                let color = value
+                    .as_str()
+                    .and_then(|color| Color::from_str(color).ok())
+                    .ok_or_else(|| {
+                        trf(Message::UnknownColor, &[&config::describe(value), &name])
+                    })?;
+                *style = style.fg(color);
+            }
+        }
+        if let Some(value) = section.get("highlight") {
+            let color = value
+                .as_str()
+                .and_then(|color| Color::from_str(color).ok())
+                .ok_or_else(|| {
+                    trf(
+                        Message::UnknownColor,
+                        &[&config::describe(value), &"highlight"],
+                    )
+                })?;
+            theme.highlight = Style::new().bg(color);
+        }
+        if let Some(value) = section.get("border") {
+            theme.border = match value.as_str() {
+                Some("plain") => border::PLAIN,
+                Some("rounded") => border::ROUNDED,
+                Some("double") => border::DOUBLE,
+                Some("thick") => border::THICK,
+                Some("quadrant-inside") => border::QUADRANT_INSIDE,
+                Some("quadrant-outside") => border::QUADRANT_OUTSIDE,
+                _ => return Err(trf(Message::UnknownBorder, &[&config::describe(value)])),
+            };
+        }
+        Ok(theme)
+    }
+}